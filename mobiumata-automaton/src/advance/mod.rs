@@ -0,0 +1,273 @@
+use crate::{CellularAutomaton, ElementaryCellularAutomaton, Rule, Wrap};
+
+/// Lookup table which advances a row by two generations, four cells at a
+/// time, from the eight cells surrounding each block.
+pub struct StepTable {
+    rule: Rule,
+    table: [u8; 256],
+}
+
+impl StepTable {
+    pub const GENERATIONS: usize = 2;
+    const BLOCK: usize = 4;
+
+    pub fn new(rule: Rule) -> Self {
+        let mut table = [0; 256];
+        for (window, output) in table.iter_mut().enumerate() {
+            let mut cells: [bool; 8] = core::array::from_fn(|i| (window >> i) & 1 == 1);
+            for generation in 1..=Self::GENERATIONS {
+                let previous = cells;
+                for i in generation..8 - generation {
+                    cells[i] = rule.apply(previous[i - 1], previous[i], previous[i + 1]);
+                }
+            }
+            *output = (0..Self::BLOCK).fold(0, |block, i| block | (cells[i + 2] as u8) << i);
+        }
+        Self { rule, table }
+    }
+
+    /// Advances a row by `StepTable::GENERATIONS` generations. On a wrapping
+    /// row every block is looked up; otherwise blocks within two cells of an
+    /// edge are stepped directly.
+    pub fn advance<const W: usize>(&self, wrap: Wrap, state: &[bool; W]) -> [bool; W] {
+        // Blocks whose eight cell window lies inside the row need no edges
+        let looked_up = |start: usize| wrap == Wrap::Wrap || (start >= 2 && start + 6 <= W);
+
+        let mut next_state = [false; W];
+        for start in (0..W)
+            .step_by(Self::BLOCK)
+            .filter(|&start| looked_up(start))
+        {
+            let window = (0..8).fold(0, |window, i| {
+                let cell = state[(start + i + 2 * W - 2) % W];
+                window | (cell as usize) << i
+            });
+            let block = self.table[window];
+            for (i, cell) in next_state[start..].iter_mut().take(Self::BLOCK).enumerate() {
+                *cell = (block >> i) & 1 == 1;
+            }
+        }
+
+        if wrap != Wrap::Wrap && W > 0 {
+            let step = |i: usize| {
+                let (left, right) = (wrap.left(state, i), wrap.right(state, i));
+                self.rule.apply(left, state[i], right)
+            };
+            let (left_edge, right_edge) = wrap.edges(step(0), step(W - 1));
+            for (i, cell) in next_state.iter_mut().enumerate() {
+                if !looked_up(i - i % Self::BLOCK) {
+                    let left = if i > 0 { step(i - 1) } else { left_edge };
+                    let right = if i + 1 < W { step(i + 1) } else { right_edge };
+                    *cell = self.rule.apply(left, step(i), right);
+                }
+            }
+        }
+        next_state
+    }
+}
+
+impl ElementaryCellularAutomaton {
+    /// Returns the state `generations` generations after `state` without
+    /// simulating every generation where possible.
+    ///
+    /// Additive rules take `O(W log n)` steps on a wrapping row and
+    /// `O(W³ log n)` on any other. Other rules advance two generations at a
+    /// time using a `StepTable`, and skip whole cycles once `generations`
+    /// exceeds the `2^W` possible states, as the row must then repeat.
+    pub fn advance<const W: usize>(&self, state: &[bool; W], generations: usize) -> [bool; W] {
+        if let Some(coefficients) = self.rule.additive_coefficients() {
+            return if self.wrap == Wrap::Wrap {
+                advance_additive(coefficients, state, generations)
+            } else {
+                self.advance_affine(state, generations)
+            };
+        }
+
+        let state_space = u32::try_from(W).ok().and_then(|w| 1usize.checked_shl(w));
+        let generations = match state_space {
+            Some(states) if generations > states => match self.cycle::<W>(state, generations) {
+                Some(cycle) => cycle.transient + (generations - cycle.transient) % cycle.period,
+                None => generations,
+            },
+            _ => generations,
+        };
+
+        let mut state = *state;
+        let mut remaining = generations;
+        if remaining >= StepTable::GENERATIONS {
+            let table = StepTable::new(self.rule);
+            while remaining >= StepTable::GENERATIONS {
+                state = table.advance(self.wrap, &state);
+                remaining -= StepTable::GENERATIONS;
+            }
+        }
+        for _ in 0..remaining {
+            let mut next_state = [false; W];
+            self.next(&state, &mut next_state);
+            state = next_state;
+        }
+        state
+    }
+
+    /// Advances an additive rule under a boundary other than wrapping, where
+    /// each generation is an affine map `s ↦ Ms + v` over GF(2), by repeatedly
+    /// squaring the map. `M` is stored as `W` columns of `W` cells.
+    fn advance_affine<const W: usize>(&self, state: &[bool; W], generations: usize) -> [bool; W] {
+        let step = |state: &[bool; W]| {
+            let mut next_state = [false; W];
+            self.next(state, &mut next_state);
+            next_state
+        };
+        let linear = |columns: &[[bool; W]; W], state: &[bool; W]| {
+            let mut result = [false; W];
+            for (column, _) in columns.iter().zip(state).filter(|(_, &cell)| cell) {
+                for (cell, &other) in result.iter_mut().zip(column) {
+                    *cell ^= other;
+                }
+            }
+            result
+        };
+        let affine = |columns: &[[bool; W]; W], offset: &[bool; W], state: &[bool; W]| {
+            let mut result = linear(columns, state);
+            for (cell, &other) in result.iter_mut().zip(offset) {
+                *cell ^= other;
+            }
+            result
+        };
+
+        let mut offset = step(&[false; W]);
+        let mut columns: [[bool; W]; W] = core::array::from_fn(|j| {
+            let mut unit = [false; W];
+            unit[j] = true;
+            let image = step(&unit);
+            core::array::from_fn(|i| image[i] ^ offset[i])
+        });
+
+        let mut state = *state;
+        let mut remaining = generations;
+        while remaining > 0 {
+            if remaining & 1 == 1 {
+                state = affine(&columns, &offset, &state);
+            }
+            remaining >>= 1;
+            if remaining > 0 {
+                offset = affine(&columns, &offset, &offset);
+                columns = core::array::from_fn(|j| linear(&columns, &columns[j]));
+            }
+        }
+        state
+    }
+}
+
+/// Advances an additive rule on a wrapping row by treating each generation as
+/// multiplication by `a/x + b + c·x` over GF(2), whose `2^k`th power is
+/// `a/x^(2^k) + b + c·x^(2^k)`.
+fn advance_additive<const W: usize>(
+    (left, center, right): (bool, bool, bool),
+    state: &[bool; W],
+    generations: usize,
+) -> [bool; W] {
+    let mut state = *state;
+    let mut shift = 1 % W.max(1);
+    let mut remaining = generations;
+    while remaining > 0 {
+        if remaining & 1 == 1 {
+            state = core::array::from_fn(|i| {
+                (left & state[(i + W - shift) % W])
+                    ^ (center & state[i])
+                    ^ (right & state[(i + shift) % W])
+            });
+        }
+        shift = shift * 2 % W.max(1);
+        remaining >>= 1;
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simulate<const W: usize>(
+        automaton: &ElementaryCellularAutomaton,
+        state: &[bool; W],
+        generations: usize,
+    ) -> [bool; W] {
+        let mut state = *state;
+        for _ in 0..generations {
+            let mut next_state = [false; W];
+            automaton.next(&state, &mut next_state);
+            state = next_state;
+        }
+        state
+    }
+
+    #[test]
+    fn test_advance() {
+        let initial_state = [
+            true, false, false, true, true, false, true, false, false, false, true, true, false,
+        ];
+
+        for wrap in [
+            Wrap::Wrap,
+            Wrap::Zero,
+            Wrap::One,
+            Wrap::Reflect,
+            Wrap::input(true, false),
+        ] {
+            for rule in [30, 54, 60, 90, 102, 110, 150, 170, 184] {
+                let automaton = ElementaryCellularAutomaton::new(wrap, Rule::new(rule));
+                for generations in [0, 1, 2, 7, 64, 1001] {
+                    assert_eq!(
+                        automaton.advance(&initial_state, generations),
+                        simulate(&automaton, &initial_state, generations),
+                        "rule {} {:?} {}",
+                        rule,
+                        wrap,
+                        generations
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_advance_far_future() {
+        // Rule 60 clears a wrapping row whose width is a power of two
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(60));
+        let initial_state = [true, true, false, true, false, false, false, true];
+        assert_eq!(automaton.advance(&initial_state, usize::MAX), [false; 8]);
+
+        let generations = 1_000_000_007;
+        for (wrap, rule) in [
+            (Wrap::Wrap, 30),
+            (Wrap::Zero, 110),
+            (Wrap::Zero, 150),
+            (Wrap::One, 90),
+            (Wrap::Reflect, 60),
+        ] {
+            let automaton = ElementaryCellularAutomaton::new(wrap, Rule::new(rule));
+            let cycle = automaton.cycle::<8>(&initial_state, 1024).unwrap();
+            let equivalent = cycle.transient + (generations - cycle.transient) % cycle.period;
+            assert_eq!(
+                automaton.advance(&initial_state, generations),
+                simulate(&automaton, &initial_state, equivalent)
+            );
+        }
+    }
+
+    #[test]
+    fn test_step_table() {
+        let table = StepTable::new(Rule::new(110));
+
+        for wrap in [Wrap::Wrap, Wrap::Zero, Wrap::One, Wrap::Reflect] {
+            let automaton = ElementaryCellularAutomaton::new(wrap, Rule::new(110));
+            let state = [true, false, true, true, false, false, true];
+            assert_eq!(table.advance(wrap, &state), simulate(&automaton, &state, 2));
+            let state = [true, false];
+            assert_eq!(table.advance(wrap, &state), simulate(&automaton, &state, 2));
+            let state: [bool; 23] = core::array::from_fn(|i| i % 3 == 0 || i % 5 == 1);
+            assert_eq!(table.advance(wrap, &state), simulate(&automaton, &state, 2));
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use core::ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr};
+
+/// An unsigned integer used to store cells, one per bit, least significant
+/// bit first.
+pub trait Word:
+    Copy
+    + PartialEq
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+{
+    const BITS: u32;
+    const ZERO: Self;
+    const ONES: Self;
+
+    fn from_bit(bit: bool) -> Self;
+
+    fn bit(self, index: u32) -> bool {
+        (self >> index) & Self::from_bit(true) == Self::from_bit(true)
+    }
+
+    /// Mask covering the bits used by the last word of a `width` cell row.
+    fn last_mask(width: usize) -> Self {
+        match (width % Self::BITS as usize) as u32 {
+            0 => Self::ONES,
+            used => !(Self::ONES << used),
+        }
+    }
+}
+
+macro_rules! impl_word {
+    ($($ty:ty),*) => {
+        $(
+            impl Word for $ty {
+                const BITS: u32 = <$ty>::BITS;
+                const ZERO: Self = 0;
+                const ONES: Self = <$ty>::MAX;
+
+                fn from_bit(bit: bool) -> Self {
+                    bit as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_word!(u8, u16, u32, u64);
+
+/// Number of `T` words needed to store `width` cells.
+pub fn word_count<T: Word>(width: usize) -> usize {
+    width.div_ceil(T::BITS as usize)
+}
+
+/// A row of up to `N * T::BITS` cells packed into an array of words.
+///
+/// Cell `i` is stored in bit `i % T::BITS` of word `i / T::BITS`. Bits beyond
+/// `width` are always zero.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BitRow<T: Word, const N: usize> {
+    words: [T; N],
+    width: usize,
+}
+
+impl<T: Word, const N: usize> BitRow<T, N> {
+    pub fn new(width: usize) -> Self {
+        assert!(width <= N * T::BITS as usize);
+        Self {
+            words: [T::ZERO; N],
+            width,
+        }
+    }
+
+    pub fn from_bools(cells: &[bool]) -> Self {
+        let mut row = Self::new(cells.len());
+        for (i, &cell) in cells.iter().enumerate() {
+            row.set(i, cell);
+        }
+        row
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn words(&self) -> &[T] {
+        &self.words[..word_count::<T>(self.width)]
+    }
+
+    /// Callers must leave the bits beyond `width` zero.
+    pub(crate) fn words_mut(&mut self) -> &mut [T] {
+        let count = word_count::<T>(self.width);
+        &mut self.words[..count]
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.width);
+        self.words[i / T::BITS as usize].bit(i as u32 % T::BITS)
+    }
+
+    pub fn set(&mut self, i: usize, cell: bool) {
+        assert!(i < self.width);
+        let word = &mut self.words[i / T::BITS as usize];
+        let mask = T::from_bit(true) << (i as u32 % T::BITS);
+        *word = if cell { *word | mask } else { *word & !mask };
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.width).map(|i| self.get(i))
+    }
+
+    pub fn write_bools(&self, cells: &mut [bool]) {
+        assert_eq!(cells.len(), self.width);
+        for (i, cell) in cells.iter_mut().enumerate() {
+            *cell = self.get(i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_row_round_trip() {
+        let cells = [
+            true, false, false, true, true, false, true, false, true, true,
+        ];
+        let row = BitRow::<u8, 2>::from_bools(&cells);
+
+        assert_eq!(row.words(), &[0b0101_1001, 0b11]);
+
+        let mut round_trip = [false; 10];
+        row.write_bools(&mut round_trip);
+        assert_eq!(round_trip, cells);
+    }
+
+    #[test]
+    fn test_last_mask() {
+        assert_eq!(u8::last_mask(8), 0xff);
+        assert_eq!(u8::last_mask(10), 0b11);
+        assert_eq!(u64::last_mask(63), u64::MAX >> 1);
+    }
+}
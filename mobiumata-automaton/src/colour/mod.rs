@@ -0,0 +1,238 @@
+use defmt::Format;
+use serde::{Deserialize, Serialize};
+
+use crate::{CellularAutomaton, ParseRuleError, Wrap};
+
+#[derive(Clone, Copy, Debug, PartialEq, Format, Serialize, Deserialize)]
+pub enum ColourRuleKind {
+    /// Looks up the output for every `(left, center, right)` neighbourhood.
+    General,
+    /// Looks up the output only for the sum of the neighbourhood.
+    Totalistic,
+}
+
+/// A nearest-neighbour rule over `colours` cell states, numbered using
+/// Wolfram's scheme.
+#[derive(Clone, Copy, Debug, PartialEq, Format, Serialize, Deserialize)]
+#[serde(try_from = "RawColourRule")]
+pub struct ColourRule {
+    kind: ColourRuleKind,
+    colours: u8,
+    code: u128,
+}
+
+/// Unchecked form of [`ColourRule`], validated on deserialisation.
+#[derive(Deserialize)]
+struct RawColourRule {
+    kind: ColourRuleKind,
+    colours: u8,
+    code: u128,
+}
+
+impl TryFrom<RawColourRule> for ColourRule {
+    type Error = ParseRuleError;
+
+    fn try_from(raw: RawColourRule) -> Result<Self, Self::Error> {
+        Self::try_new(raw.kind, raw.colours, raw.code)
+    }
+}
+
+impl ColourRule {
+    pub const MAX_COLOURS: u8 = 4;
+
+    /// Builds a general rule from known-good literals such as
+    /// `general(2, 110)`, panicking if they are out of range. Use
+    /// [`try_new`](Self::try_new) for untrusted input.
+    pub fn general(colours: u8, code: u128) -> Self {
+        Self::try_new(ColourRuleKind::General, colours, code).expect("invalid colour rule")
+    }
+
+    /// Builds a totalistic rule from known-good literals such as
+    /// `totalistic(3, 777)`, panicking if they are out of range. Use
+    /// [`try_new`](Self::try_new) for untrusted input.
+    pub fn totalistic(colours: u8, code: u128) -> Self {
+        Self::try_new(ColourRuleKind::Totalistic, colours, code).expect("invalid colour rule")
+    }
+
+    /// Returns an error unless `colours` is between 2 and
+    /// [`MAX_COLOURS`](Self::MAX_COLOURS) and `code` has at most one digit
+    /// per neighbourhood.
+    pub fn try_new(kind: ColourRuleKind, colours: u8, code: u128) -> Result<Self, ParseRuleError> {
+        let rule = Self {
+            kind,
+            colours,
+            code,
+        };
+        if rule.is_valid() {
+            Ok(rule)
+        } else {
+            Err(ParseRuleError::OutOfRange)
+        }
+    }
+
+    pub fn kind(&self) -> ColourRuleKind {
+        self.kind
+    }
+
+    pub fn colours(&self) -> u8 {
+        self.colours
+    }
+
+    pub fn code(&self) -> u128 {
+        self.code
+    }
+
+    /// Number of base-`colours` digits in the rule code.
+    fn digits(&self) -> usize {
+        let colours = self.colours() as usize;
+        match self.kind {
+            ColourRuleKind::General => colours * colours * colours,
+            ColourRuleKind::Totalistic => 3 * (colours - 1) + 1,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        let colours = self.colours();
+        (2..=Self::MAX_COLOURS).contains(&colours)
+            && (colours as u128)
+                .checked_pow(self.digits() as u32)
+                .is_none_or(|limit| self.code() < limit)
+    }
+
+    /// Expands the rule code into a table of outputs indexed by neighbourhood.
+    fn table(&self) -> [u8; 64] {
+        let colours = self.colours() as u128;
+        let mut code = self.code();
+        let mut table = [0; 64];
+        for output in table.iter_mut().take(self.digits()) {
+            *output = (code % colours) as u8;
+            code /= colours;
+        }
+        table
+    }
+}
+
+pub struct ColourCellularAutomaton {
+    pub wrap: Wrap,
+    rule: ColourRule,
+    table: [u8; 64],
+}
+
+impl ColourCellularAutomaton {
+    pub fn new(wrapping: Wrap, rule: ColourRule) -> Self {
+        Self {
+            wrap: wrapping,
+            rule,
+            table: rule.table(),
+        }
+    }
+
+    pub fn rule(&self) -> ColourRule {
+        self.rule
+    }
+}
+
+impl CellularAutomaton for ColourCellularAutomaton {
+    type Cell = u8;
+
+    /// Reads cells as their value modulo the rule's colours, so a cell out of
+    /// range cannot index past the rule table.
+    fn next(&self, state: &[u8], next_state: &mut [u8]) {
+        assert_eq!(state.len(), next_state.len());
+
+        let colours = self.rule.colours();
+
+        for (i, cell) in next_state.iter_mut().enumerate() {
+            let left = self.wrap.left(state, i) % colours;
+            let center = state[i] % colours;
+            let right = self.wrap.right(state, i) % colours;
+
+            let index = match self.rule.kind {
+                ColourRuleKind::General => (left * colours + center) * colours + right,
+                ColourRuleKind::Totalistic => left + center + right,
+            };
+            *cell = self.table[index as usize];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ElementaryCellularAutomaton, Rule};
+
+    #[test]
+    fn test_colour_cellular_automaton_totalistic() {
+        // https://mathworld.wolfram.com/TotalisticCellularAutomaton.html
+        let automaton = ColourCellularAutomaton::new(Wrap::Zero, ColourRule::totalistic(3, 777));
+        let mut state = [[0; 7]; 3];
+        state[0][3] = 1;
+
+        automaton.next_row(&mut state, 1);
+        assert_eq!(state[1], [0, 0, 1, 1, 1, 0, 0]);
+
+        automaton.next_row(&mut state, 2);
+        assert_eq!(state[2], [0, 1, 2, 1, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_colour_cellular_automaton_general_matches_elementary() {
+        let initial_state = [false, false, true, false, true, true, false, false];
+
+        for wrap in [Wrap::Wrap, Wrap::Zero, Wrap::One] {
+            let elementary = ElementaryCellularAutomaton::new(wrap, Rule::new(110));
+            let colour = ColourCellularAutomaton::new(wrap, ColourRule::general(2, 110));
+
+            let mut expected = [false; 8];
+            elementary.next(&initial_state, &mut expected);
+            let mut next_state = [0; 8];
+            colour.next(&initial_state.map(u8::from), &mut next_state);
+
+            assert_eq!(next_state, expected.map(u8::from));
+            assert_eq!(
+                colour.period::<8, 192>(&initial_state.map(u8::from)),
+                elementary.period::<8, 192>(&initial_state)
+            );
+        }
+    }
+
+    #[test]
+    fn test_colour_cellular_automaton_out_of_range_cells() {
+        let automaton = ColourCellularAutomaton::new(Wrap::Wrap, ColourRule::general(4, 0));
+        let mut next_state = [0; 4];
+        automaton.next(&[255, 4, 7, 1], &mut next_state);
+        assert_eq!(next_state, [0; 4]);
+
+        let automaton = ColourCellularAutomaton::new(Wrap::Zero, ColourRule::totalistic(3, 777));
+        let mut expected = [0; 7];
+        automaton.next(&[0, 0, 0, 1, 0, 0, 0], &mut expected);
+        let mut next_state = [0; 7];
+        automaton.next(&[3, 6, 0, 4, 0, 0, 3], &mut next_state);
+        assert_eq!(next_state, expected);
+    }
+
+    #[test]
+    fn test_colour_rule_validation() {
+        assert_eq!(
+            ColourRule::try_new(ColourRuleKind::Totalistic, 1, 0),
+            Err(ParseRuleError::OutOfRange)
+        );
+        assert_eq!(
+            ColourRule::try_new(ColourRuleKind::General, 5, 0),
+            Err(ParseRuleError::OutOfRange)
+        );
+
+        let json = r#"{"kind":"Totalistic","colours":3,"code":777}"#;
+        assert_eq!(
+            serde_json::from_str::<ColourRule>(json).unwrap(),
+            ColourRule::totalistic(3, 777)
+        );
+        for json in [
+            r#"{"kind":"Totalistic","colours":0,"code":0}"#,
+            r#"{"kind":"General","colours":9,"code":1}"#,
+            r#"{"kind":"Totalistic","colours":3,"code":2187}"#,
+        ] {
+            assert!(serde_json::from_str::<ColourRule>(json).is_err());
+        }
+    }
+}
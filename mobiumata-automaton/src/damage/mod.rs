@@ -0,0 +1,232 @@
+use defmt::Format;
+
+use crate::math::log2;
+use crate::{check_width, AutomatonError, CellularAutomaton};
+
+/// Runs an automaton alongside a shadow copy whose initial state differs in a
+/// single cell, yielding the Hamming distance between them each generation.
+pub struct DamageSpreading<'a, A: CellularAutomaton<Cell = bool>, const W: usize> {
+    automaton: &'a A,
+    state: [bool; W],
+    shadow: [bool; W],
+}
+
+impl<'a, A: CellularAutomaton<Cell = bool>, const W: usize> DamageSpreading<'a, A, W> {
+    /// Returns an error if `cell` is outside the row or the automaton cannot
+    /// step rows of `W` cells.
+    pub fn new(automaton: &'a A, initial: [bool; W], cell: usize) -> Result<Self, AutomatonError> {
+        if cell >= W {
+            return Err(AutomatonError::CellOutOfRange {
+                index: cell,
+                width: W,
+            });
+        }
+        check_width(automaton, W)?;
+
+        let mut shadow = initial;
+        shadow[cell] = !shadow[cell];
+        Ok(Self {
+            automaton,
+            state: initial,
+            shadow,
+        })
+    }
+
+    pub fn state(&self) -> &[bool; W] {
+        &self.state
+    }
+
+    pub fn shadow(&self) -> &[bool; W] {
+        &self.shadow
+    }
+
+    /// Cells which differ between the automaton and its shadow.
+    pub fn difference(&self) -> [bool; W] {
+        core::array::from_fn(|i| self.state[i] != self.shadow[i])
+    }
+
+    pub fn distance(&self) -> usize {
+        (0..W).filter(|&i| self.state[i] != self.shadow[i]).count()
+    }
+
+    pub fn step(&mut self) {
+        let (state, shadow) = (self.state, self.shadow);
+        self.automaton.next(&state, &mut self.state);
+        self.automaton.next(&shadow, &mut self.shadow);
+    }
+}
+
+impl<A: CellularAutomaton<Cell = bool>, const W: usize> Iterator for DamageSpreading<'_, A, W> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let distance = self.distance();
+        self.step();
+        Some(distance)
+    }
+}
+
+/// How strongly an automaton amplifies a single flipped cell.
+#[derive(Clone, Copy, Debug, PartialEq, Format)]
+pub struct Sensitivity {
+    /// Cells per generation the damage spreads to the left of the flipped
+    /// cell, negative if its left edge moves right.
+    pub left_speed: f32,
+    /// Cells per generation the damage spreads to the right.
+    pub right_speed: f32,
+    /// Estimated maximum Lyapunov exponent in bits per generation, negative
+    /// infinity if the damage dies out.
+    pub lyapunov: f32,
+}
+
+/// Measures the spreading of damage from flipping `cell` of `initial` over
+/// `generations` generations.
+///
+/// Front speeds are measured only until the light cone would wrap around the
+/// row. The Lyapunov exponent follows Bagnoli et al., counting the damage
+/// paths through the Boolean derivative of each generation.
+///
+/// Returns an error for no generations, or for the same inputs as
+/// [`DamageSpreading::new`].
+pub fn measure<A: CellularAutomaton<Cell = bool>, const W: usize>(
+    automaton: &A,
+    initial: [bool; W],
+    cell: usize,
+    generations: usize,
+) -> Result<Sensitivity, AutomatonError> {
+    if generations == 0 {
+        return Err(AutomatonError::NoGenerations);
+    }
+
+    let mut spreading = DamageSpreading::new(automaton, initial, cell)?;
+    let horizon = generations.min(W.saturating_sub(1) / 2).max(1);
+    for _ in 0..horizon {
+        spreading.step();
+    }
+    let offsets = || {
+        let difference = spreading.difference();
+        let horizon = horizon as isize;
+        (-horizon..=horizon).filter(move |&offset| {
+            difference[(cell as isize + offset).rem_euclid(W as isize) as usize]
+        })
+    };
+    let (left_speed, right_speed) = match (offsets().min(), offsets().max()) {
+        (Some(left), Some(right)) => (-left as f32 / horizon as f32, right as f32 / horizon as f32),
+        _ => (0.0, 0.0),
+    };
+
+    // Propagate the number of damage paths to each cell, normalising every
+    // generation and accumulating the growth to avoid overflow
+    let mut state = initial;
+    let mut damage = [0.0f32; W];
+    damage[cell] = 1.0;
+    let mut growth = 0.0;
+    for _ in 0..generations {
+        let mut next_state = [false; W];
+        automaton.next(&state, &mut next_state);
+
+        let mut next_damage = [0.0f32; W];
+        for j in (0..W).filter(|&j| damage[j] > 0.0) {
+            let mut flipped = state;
+            flipped[j] = !flipped[j];
+            let mut next_flipped = [false; W];
+            automaton.next(&flipped, &mut next_flipped);
+            for i in (0..W).filter(|&i| next_flipped[i] != next_state[i]) {
+                next_damage[i] += damage[j];
+            }
+        }
+
+        let total: f32 = next_damage.iter().sum();
+        if total == 0.0 {
+            return Ok(Sensitivity {
+                left_speed,
+                right_speed,
+                lyapunov: f32::NEG_INFINITY,
+            });
+        }
+        growth += log2(total);
+        damage = next_damage.map(|paths| paths / total);
+        state = next_state;
+    }
+
+    Ok(Sensitivity {
+        left_speed,
+        right_speed,
+        lyapunov: growth / generations as f32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ElementaryCellularAutomaton, HybridCellularAutomaton, Rule, Wrap};
+
+    #[test]
+    fn test_damage_spreading_distance() {
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(90));
+        let mut spreading = DamageSpreading::new(&automaton, [false; 32], 16).unwrap();
+
+        let distances: [usize; 8] = core::array::from_fn(|_| spreading.next().unwrap());
+        assert_eq!(distances, [1, 2, 2, 4, 2, 4, 4, 8]);
+        assert_eq!(spreading.difference(), *spreading.shadow());
+
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(0));
+        let mut spreading = DamageSpreading::new(&automaton, [true; 8], 3).unwrap();
+        let distances: [usize; 3] = core::array::from_fn(|_| spreading.next().unwrap());
+        assert_eq!(distances, [1, 0, 0]);
+    }
+
+    #[test]
+    fn test_sensitivity() {
+        let initial: [bool; 33] = core::array::from_fn(|i| i % 3 == 0 || i % 7 == 0);
+        let measure_rule = |rule| {
+            let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(rule));
+            measure(&automaton, initial, 16, 40).unwrap()
+        };
+
+        let rule_90 = measure_rule(90);
+        assert_eq!((rule_90.left_speed, rule_90.right_speed), (1.0, 1.0));
+        assert!((rule_90.lyapunov - 1.0).abs() < 1e-4);
+
+        let rule_204 = measure_rule(204);
+        assert_eq!(
+            rule_204,
+            Sensitivity {
+                left_speed: 0.0,
+                right_speed: 0.0,
+                lyapunov: 0.0
+            }
+        );
+
+        let rule_170 = measure_rule(170);
+        assert_eq!((rule_170.left_speed, rule_170.right_speed), (1.0, -1.0));
+
+        assert_eq!(measure_rule(0).lyapunov, f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_damage_invalid_input() {
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(90));
+        assert_eq!(
+            DamageSpreading::new(&automaton, [false; 8], 8).err(),
+            Some(AutomatonError::CellOutOfRange { index: 8, width: 8 })
+        );
+        assert_eq!(
+            measure(&automaton, [false; 8], 4, 0),
+            Err(AutomatonError::NoGenerations)
+        );
+        assert_eq!(
+            measure(&automaton, [false; 0], 0, 4),
+            Err(AutomatonError::CellOutOfRange { index: 0, width: 0 })
+        );
+
+        let hybrid = HybridCellularAutomaton::new(Wrap::Wrap, [Rule::new(90); 4]);
+        assert_eq!(
+            measure(&hybrid, [false; 8], 4, 4),
+            Err(AutomatonError::LengthMismatch {
+                expected: 4,
+                actual: 8
+            })
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use defmt::Format;
+use serde::{Deserialize, Serialize};
+
+/// A domain which repeats every `space` cells and every `time` generations,
+/// shifted right by `shift` cells.
+#[derive(Clone, Copy, Debug, PartialEq, Format, Serialize, Deserialize)]
+pub struct DomainPeriod {
+    pub space: usize,
+    pub time: usize,
+    pub shift: isize,
+}
+
+/// Separates the regular background domain of a space-time diagram from the
+/// particles and defects moving through it.
+#[derive(Clone, Copy, Debug, PartialEq, Format, Serialize, Deserialize)]
+pub enum DomainFilter {
+    /// Cells whose local pattern, the three cells around them and the three
+    /// above, makes up less than the given fraction of all the patterns in
+    /// the window are particles.
+    Frequency(f32),
+    /// Cells breaking either period of the domain are particles.
+    Periodic(DomainPeriod),
+}
+
+impl DomainFilter {
+    /// The period 4 domain of rule 54.
+    pub const RULE_54: Self = Self::Periodic(DomainPeriod {
+        space: 4,
+        time: 4,
+        shift: 0,
+    });
+    /// The ether of rule 110.
+    pub const RULE_110: Self = Self::Periodic(DomainPeriod {
+        space: 14,
+        time: 7,
+        shift: 0,
+    });
+
+    /// Whether the filter can be applied to rows of `width` cells: a
+    /// threshold between 0 and 1, or non-zero periods whose spatial period
+    /// divides the width, as the row wraps around, with a smaller shift.
+    pub fn is_valid(&self, width: usize) -> bool {
+        match *self {
+            Self::Frequency(threshold) => (0.0..=1.0).contains(&threshold),
+            Self::Periodic(DomainPeriod { space, time, shift }) => {
+                space > 0 && time > 0 && width.is_multiple_of(space) && shift.unsigned_abs() < space
+            }
+        }
+    }
+
+    /// Marks the particles in a ring of rows whose oldest row is at `head`,
+    /// as used by [`SpaceTime`](crate::SpaceTime) and the display. Rows too
+    /// close to the oldest to be classified are treated as domain, as is
+    /// every row if the filter is not valid for the width.
+    pub fn classify<const W: usize, const H: usize>(
+        &self,
+        state: &[[bool; W]; H],
+        head: usize,
+        particles: &mut [[bool; W]; H],
+    ) {
+        if H == 0 {
+            return;
+        }
+        if !self.is_valid(W) {
+            *particles = [[false; W]; H];
+            return;
+        }
+
+        let row = |generation: usize| &state[(head + generation) % H];
+        let cell = |generation: usize, i: isize| row(generation)[i.rem_euclid(W as isize) as usize];
+
+        match *self {
+            Self::Frequency(threshold) => {
+                let pattern = |generation: usize, i: isize| {
+                    (-1..=1).fold(0, |pattern, offset| {
+                        pattern << 2
+                            | (cell(generation - 1, i + offset) as usize) << 1
+                            | cell(generation, i + offset) as usize
+                    })
+                };
+
+                let mut counts = [0usize; 64];
+                for generation in 1..H {
+                    for i in 0..W as isize {
+                        counts[pattern(generation, i)] += 1;
+                    }
+                }
+                let minimum = threshold * ((H - 1) * W) as f32;
+
+                particles[head] = [false; W];
+                for generation in 1..H {
+                    let index = (head + generation) % H;
+                    for (i, particle) in particles[index].iter_mut().enumerate() {
+                        *particle = (counts[pattern(generation, i as isize)] as f32) < minimum;
+                    }
+                }
+            }
+            Self::Periodic(DomainPeriod { space, time, shift }) => {
+                let (space, time) = (space as isize, time.min(H - 1));
+                for generation in 0..H {
+                    let index = (head + generation) % H;
+                    for (i, particle) in particles[index].iter_mut().enumerate() {
+                        let i = i as isize;
+                        let here = cell(generation, i);
+                        *particle = generation >= time
+                            && (here != cell(generation, i + space)
+                                || here != cell(generation, i - space)
+                                || here != cell(generation - time, i - shift));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CellularAutomaton, ElementaryCellularAutomaton, Rule, SpaceTime, Wrap};
+
+    fn ether() -> [bool; 56] {
+        core::array::from_fn(|i| b"11111000100110"[i % 14] == b'1')
+    }
+
+    fn evolve<const H: usize>(rule: u8, initial: [bool; 56]) -> [[bool; 56]; H] {
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(rule));
+        let mut generations = automaton.evolve(initial);
+        core::array::from_fn(|_| generations.next().unwrap())
+    }
+
+    #[test]
+    fn test_periodic_filter() {
+        let mut particles = [[true; 56]; 32];
+        let state = evolve::<32>(110, ether());
+        DomainFilter::RULE_110.classify(&state, 0, &mut particles);
+        assert_eq!(particles, [[false; 56]; 32]);
+
+        // A defect in the ether shows up as particles within its light cone
+        let mut initial = ether();
+        initial[20] = !initial[20];
+        let state = evolve::<32>(110, initial);
+        DomainFilter::RULE_110.classify(&state, 0, &mut particles);
+        assert!(particles[10].contains(&true));
+        assert!(!particles[10][45..52].contains(&true));
+    }
+
+    #[test]
+    fn test_classify_ring() {
+        // Classifying a ring matches classifying the same rows in order
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(54));
+        let initial: [bool; 56] = core::array::from_fn(|i| (i * 7) % 11 < 4);
+
+        let mut ring = [[false; 56]; 24];
+        ring[23] = initial;
+        let mut diagram = SpaceTime::new(&mut ring);
+        for _ in 0..40 {
+            diagram.step(&automaton);
+        }
+        let head = diagram.head();
+        let latest = *diagram.latest();
+        let ordered = evolve::<24>(54, *diagram.generation(23));
+        assert_eq!(ordered[23], latest);
+
+        for filter in [DomainFilter::RULE_54, DomainFilter::Frequency(0.05)] {
+            let mut expected = [[false; 56]; 24];
+            filter.classify(&ordered, 0, &mut expected);
+            let mut particles = [[false; 56]; 24];
+            filter.classify(&ring, head, &mut particles);
+            for generation in 0..24 {
+                assert_eq!(particles[(head + generation) % 24], expected[generation]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_frequency_filter() {
+        let mut particles = [[true; 56]; 32];
+        let state = evolve::<32>(110, ether());
+        DomainFilter::Frequency(0.01).classify(&state, 0, &mut particles);
+        assert_eq!(particles, [[false; 56]; 32]);
+
+        let mut initial = ether();
+        initial[20] = !initial[20];
+        let state = evolve::<32>(110, initial);
+        DomainFilter::Frequency(0.01).classify(&state, 0, &mut particles);
+        assert!(particles.iter().flatten().any(|&particle| particle));
+        assert!(!particles[0].contains(&true));
+    }
+
+    #[test]
+    fn test_filter_validity() {
+        assert!(DomainFilter::RULE_54.is_valid(8));
+        assert!(DomainFilter::RULE_110.is_valid(56));
+        assert!(!DomainFilter::RULE_110.is_valid(8));
+        assert!(DomainFilter::Frequency(0.05).is_valid(8));
+        assert!(!DomainFilter::Frequency(f32::NAN).is_valid(8));
+        assert!(!DomainFilter::Frequency(1.5).is_valid(8));
+        for (space, time, shift) in [(0, 4, 0), (4, 0, 0), (4, 4, -4), (usize::MAX, 1, 0)] {
+            let filter = DomainFilter::Periodic(DomainPeriod { space, time, shift });
+            assert!(!filter.is_valid(8));
+        }
+
+        // Invalid filters and empty universes classify nothing
+        let state = evolve::<32>(110, ether());
+        let mut particles = [[true; 56]; 32];
+        let filter = DomainFilter::Periodic(DomainPeriod {
+            space: 12,
+            time: 7,
+            shift: 0,
+        });
+        filter.classify(&state, 0, &mut particles);
+        assert_eq!(particles, [[false; 56]; 32]);
+        DomainFilter::RULE_110.classify(&[[false; 56]; 0], 0, &mut []);
+    }
+}
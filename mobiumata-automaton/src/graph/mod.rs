@@ -0,0 +1,263 @@
+use defmt::Format;
+
+use crate::CellularAutomaton;
+
+/// Information about one state of a `StateTransitionGraph`. States are
+/// numbered with cell `i` in bit `i`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Format)]
+pub struct StateNode {
+    pub successor: u32,
+    pub predecessors: u32,
+    /// Generations before the state reaches its attractor cycle.
+    pub transient: u32,
+    /// Lowest numbered state on the attractor cycle the state flows into.
+    pub attractor: u32,
+    basin: u32,
+    depth: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Format)]
+pub struct Attractor {
+    /// Lowest numbered state on the cycle.
+    pub state: u32,
+    pub period: u32,
+    /// Number of states, including the cycle, which flow into the attractor.
+    pub basin: u32,
+    /// Longest transient of any state in the basin.
+    pub depth: u32,
+}
+
+/// The complete state-transition graph of an automaton on a small row.
+pub struct StateTransitionGraph<'a> {
+    width: usize,
+    nodes: &'a [StateNode],
+}
+
+impl<'a> StateTransitionGraph<'a> {
+    pub const MAX_WIDTH: usize = 30;
+
+    const UNRESOLVED: u32 = u32::MAX;
+    const ON_PATH: u32 = 1 << 31;
+
+    /// Analyses every state of a `width` cell row, using `nodes` (which must
+    /// hold `1 << width` entries) as storage.
+    pub fn new<A: CellularAutomaton<Cell = bool>>(
+        automaton: &A,
+        width: usize,
+        nodes: &'a mut [StateNode],
+    ) -> Self {
+        assert!(width <= Self::MAX_WIDTH);
+        assert_eq!(nodes.len(), 1 << width);
+
+        let mut state = [false; Self::MAX_WIDTH];
+        let mut next_state = [false; Self::MAX_WIDTH];
+        for (index, node) in nodes.iter_mut().enumerate() {
+            for (i, cell) in state[..width].iter_mut().enumerate() {
+                *cell = (index >> i) & 1 == 1;
+            }
+            automaton.next(&state[..width], &mut next_state[..width]);
+            let successor = next_state[..width]
+                .iter()
+                .rev()
+                .fold(0, |successor, &cell| successor << 1 | cell as u32);
+            *node = StateNode {
+                successor,
+                attractor: Self::UNRESOLVED,
+                ..StateNode::default()
+            };
+        }
+        for index in 0..nodes.len() {
+            let successor = nodes[index].successor as usize;
+            nodes[successor].predecessors += 1;
+        }
+
+        for start in 0..nodes.len() as u32 {
+            Self::resolve(nodes, start);
+        }
+
+        for index in 0..nodes.len() {
+            let StateNode {
+                attractor,
+                transient,
+                ..
+            } = nodes[index];
+            let representative = &mut nodes[attractor as usize];
+            representative.basin += 1;
+            representative.depth = representative.depth.max(transient);
+        }
+
+        Self { width, nodes }
+    }
+
+    /// Follows the trajectory from `start` until it meets an already resolved
+    /// state or closes a new cycle, then resolves every state on the way.
+    fn resolve(nodes: &mut [StateNode], start: u32) {
+        if nodes[start as usize].attractor != Self::UNRESOLVED {
+            return;
+        }
+        let marker = Self::ON_PATH | start;
+        let successor = |nodes: &[StateNode], state: u32| nodes[state as usize].successor;
+
+        let mut state = start;
+        while nodes[state as usize].attractor == Self::UNRESOLVED {
+            nodes[state as usize].attractor = marker;
+            state = successor(nodes, state);
+        }
+
+        if nodes[state as usize].attractor == marker {
+            let mut representative = state;
+            let mut cycle_state = successor(nodes, state);
+            while cycle_state != state {
+                representative = representative.min(cycle_state);
+                cycle_state = successor(nodes, cycle_state);
+            }
+            loop {
+                nodes[cycle_state as usize].attractor = representative;
+                nodes[cycle_state as usize].transient = 0;
+                cycle_state = successor(nodes, cycle_state);
+                if cycle_state == state {
+                    break;
+                }
+            }
+        }
+
+        let mut length = 0;
+        state = start;
+        while nodes[state as usize].attractor == marker {
+            length += 1;
+            state = successor(nodes, state);
+        }
+        let StateNode {
+            attractor,
+            transient,
+            ..
+        } = nodes[state as usize];
+
+        state = start;
+        for distance in (1..=length).rev() {
+            nodes[state as usize].attractor = attractor;
+            nodes[state as usize].transient = transient + distance;
+            state = successor(nodes, state);
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn node(&self, state: u32) -> &StateNode {
+        &self.nodes[state as usize]
+    }
+
+    pub fn attractors(&self) -> impl Iterator<Item = Attractor> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(state, node)| node.attractor == *state as u32)
+            .map(|(state, node)| {
+                let state = state as u32;
+                let mut period = 1;
+                let mut cycle_state = node.successor;
+                while cycle_state != state {
+                    period += 1;
+                    cycle_state = self.node(cycle_state).successor;
+                }
+                Attractor {
+                    state,
+                    period,
+                    basin: node.basin,
+                    depth: node.depth,
+                }
+            })
+    }
+
+    /// States which have no predecessor.
+    pub fn garden_of_eden(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.nodes.len() as u32).filter(|&state| self.node(state).predecessors == 0)
+    }
+
+    /// The state which takes the most generations to reach its attractor.
+    pub fn longest_transient(&self) -> u32 {
+        (0..self.nodes.len() as u32)
+            .max_by_key(|&state| self.node(state).transient)
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use super::*;
+    use crate::{Cycle, ElementaryCellularAutomaton, Rule, Wrap};
+
+    #[test]
+    fn test_state_transition_graph_rotation() {
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(170));
+        let mut nodes = [StateNode::default(); 16];
+        let graph = StateTransitionGraph::new(&automaton, 4, &mut nodes);
+
+        let mut attractors = [(0, 0, 0); 6];
+        for (attractor, summary) in graph.attractors().zip(attractors.iter_mut()) {
+            *summary = (attractor.state, attractor.period, attractor.basin);
+        }
+        assert_eq!(
+            attractors,
+            [
+                (0, 1, 1),
+                (1, 4, 4),
+                (3, 4, 4),
+                (5, 2, 2),
+                (7, 4, 4),
+                (15, 1, 1)
+            ]
+        );
+        assert_eq!(graph.attractors().count(), 6);
+        assert_eq!(graph.garden_of_eden().count(), 0);
+    }
+
+    #[test]
+    fn test_state_transition_graph_matches_cycle() {
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(30));
+        let mut nodes = vec![StateNode::default(); 1 << 10];
+        let graph = StateTransitionGraph::new(&automaton, 10, &mut nodes);
+
+        let mut total = 0;
+        for attractor in graph.attractors() {
+            total += attractor.basin;
+        }
+        assert_eq!(total, 1 << 10);
+
+        for state in 0..1 << 10 {
+            let node = graph.node(state);
+            let initial_state: [bool; 10] = core::array::from_fn(|i| (state >> i) & 1 == 1);
+            let cycle = automaton.cycle::<10>(&initial_state, 1 << 12).unwrap();
+            let period = graph
+                .attractors()
+                .find(|attractor| attractor.state == node.attractor)
+                .unwrap()
+                .period;
+
+            assert_eq!(
+                cycle,
+                Cycle {
+                    transient: node.transient as usize,
+                    period: period as usize
+                }
+            );
+        }
+
+        let garden_of_eden = graph.garden_of_eden().count();
+        assert!(garden_of_eden > 0);
+        assert_eq!(
+            graph
+                .node(graph.garden_of_eden().next().unwrap())
+                .predecessors,
+            0
+        );
+        let longest = graph.longest_transient();
+        assert_eq!(graph.node(longest).predecessors, 0);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{CellularAutomaton, ElementaryCellularAutomaton, Rule, Wrap};
+
+/// Non-uniform automaton where each cell applies the rule for its column.
+pub struct HybridCellularAutomaton<const W: usize> {
+    pub wrap: Wrap,
+    pub rules: [Rule; W],
+}
+
+impl<const W: usize> HybridCellularAutomaton<W> {
+    pub fn new(wrapping: Wrap, rules: [Rule; W]) -> Self {
+        Self {
+            wrap: wrapping,
+            rules,
+        }
+    }
+
+    /// Linear hybrid automaton where cell `i` applies rule 150 when bit `i` of
+    /// `mask` is set and rule 90 otherwise.
+    pub fn rule_90_150(wrapping: Wrap, mask: u64) -> Self {
+        assert!(W <= 64);
+        let mut rules = [Rule::new(90); W];
+        for (i, rule) in rules.iter_mut().enumerate() {
+            if mask >> i & 1 == 1 {
+                *rule = Rule::new(150);
+            }
+        }
+        Self::new(wrapping, rules)
+    }
+}
+
+impl<const W: usize> CellularAutomaton for HybridCellularAutomaton<W> {
+    type Cell = bool;
+
+    fn next(&self, state: &[bool], next_state: &mut [bool]) {
+        assert_eq!(state.len(), W);
+        assert_eq!(next_state.len(), W);
+
+        for (i, cell) in next_state.iter_mut().enumerate() {
+            *cell = self.rules[i].apply(
+                self.wrap.left(state, i),
+                state[i],
+                self.wrap.right(state, i),
+            );
+        }
+    }
+
+    fn width(&self) -> Option<usize> {
+        Some(W)
+    }
+}
+
+/// Uniform automaton whose rule advances through `rules` every `interval`
+/// generations, returning to the first after the last.
+pub struct RuleSchedule<'a> {
+    pub wrap: Wrap,
+    pub rules: &'a [Rule],
+    pub interval: usize,
+}
+
+impl<'a> RuleSchedule<'a> {
+    pub fn new(wrapping: Wrap, rules: &'a [Rule], interval: usize) -> Self {
+        assert!(!rules.is_empty());
+        assert!(interval > 0);
+        Self {
+            wrap: wrapping,
+            rules,
+            interval,
+        }
+    }
+
+    pub fn rule(&self, generation: usize) -> Rule {
+        self.rules[generation / self.interval % self.rules.len()]
+    }
+
+    pub fn automaton(&self, generation: usize) -> ElementaryCellularAutomaton {
+        ElementaryCellularAutomaton::new(self.wrap, self.rule(generation))
+    }
+
+    /// Computes generation `generation + 1` from `state`.
+    pub fn next(&self, generation: usize, state: &[bool], next_state: &mut [bool]) {
+        self.automaton(generation).next(state, next_state);
+    }
+
+    pub fn next_row<const W: usize, const H: usize>(
+        &self,
+        generation: usize,
+        state: &mut [[bool; W]; H],
+        index: usize,
+    ) {
+        self.automaton(generation).next_row(state, index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hybrid_uniform_matches_elementary() {
+        let state = [true, false, false, true, true, false, true, false];
+        for wrap in [Wrap::Wrap, Wrap::Zero, Wrap::One, Wrap::Reflect] {
+            let hybrid = HybridCellularAutomaton::new(wrap, [Rule::new(110); 8]);
+            let elementary = ElementaryCellularAutomaton::new(wrap, Rule::new(110));
+
+            let mut hybrid_state = [false; 8];
+            let mut elementary_state = [false; 8];
+            hybrid.next(&state, &mut hybrid_state);
+            elementary.next(&state, &mut elementary_state);
+
+            assert_eq!(hybrid_state, elementary_state);
+        }
+    }
+
+    #[test]
+    fn test_hybrid_90_150_maximal_length() {
+        let automaton = HybridCellularAutomaton::<8>::rule_90_150(Wrap::Zero, 0b0000_0110);
+        assert_eq!(
+            automaton.rules[..3],
+            [Rule::new(90), Rule::new(150), Rule::new(150)]
+        );
+
+        let mut initial = [false; 8];
+        initial[0] = true;
+        let cycle = automaton.cycle::<8>(&initial, 1000).unwrap();
+
+        assert_eq!((cycle.transient, cycle.period), (0, 255));
+    }
+
+    #[test]
+    fn test_rule_schedule() {
+        let rules = [Rule::new(30), Rule::new(90), Rule::new(110)];
+        let schedule = RuleSchedule::new(Wrap::Wrap, &rules, 2);
+
+        let scheduled: [u8; 7] = core::array::from_fn(|g| schedule.rule(g).inner());
+        assert_eq!(scheduled, [30, 30, 90, 90, 110, 110, 30]);
+
+        let mut state = [[false; 8]; 4];
+        state[3][4] = true;
+        schedule.next_row(2, &mut state, 0);
+        assert_eq!(
+            state[0],
+            [false, false, false, true, false, true, false, false]
+        );
+    }
+}
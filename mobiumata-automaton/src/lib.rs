@@ -1,10 +1,66 @@
 #![no_std]
 
-use core::usize;
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::fmt;
 
 use defmt::Format;
 use serde::{Deserialize, Serialize};
 
+pub mod advance;
+pub mod bits;
+pub mod colour;
+pub mod damage;
+pub mod domain;
+pub mod graph;
+pub mod hybrid;
+pub mod life;
+mod math;
+pub mod memory;
+pub mod mobius;
+pub mod notation;
+pub mod pattern;
+pub mod preimage;
+pub mod range;
+pub mod reversible;
+pub mod search;
+pub mod seed;
+pub mod spacetime;
+pub mod statistics;
+pub mod stochastic;
+#[cfg(feature = "std")]
+pub mod text;
+pub mod turmite;
+#[cfg(feature = "alloc")]
+pub mod universe;
+
+pub use advance::StepTable;
+pub use bits::{BitRow, Word};
+pub use colour::{ColourCellularAutomaton, ColourRule, ColourRuleKind};
+pub use damage::{DamageSpreading, Sensitivity};
+pub use domain::{DomainFilter, DomainPeriod};
+pub use graph::{Attractor, StateNode, StateTransitionGraph};
+pub use hybrid::{HybridCellularAutomaton, RuleSchedule};
+pub use life::{LifeLikeAutomaton, LifeRule};
+pub use memory::{MemoryCellularAutomaton, MemoryFunction};
+pub use notation::ParseRuleError;
+pub use pattern::{Canvas, Header, PatternError, RleWriter};
+pub use preimage::Preimages;
+pub use range::{RangeCellularAutomaton, RangeRule};
+pub use reversible::ReversibleCellularAutomaton;
+pub use search::{Candidate, Genome, Search};
+pub use seed::SplitMix64;
+pub use spacetime::{Evolve, SpaceTime};
+pub use stochastic::{StochasticCellularAutomaton, Sweep, UpdateMode};
+#[cfg(feature = "std")]
+pub use text::Cells;
+pub use turmite::{Ant, Heading, TurmiteAutomaton, TurmiteRule};
+#[cfg(feature = "alloc")]
+pub use universe::Universe;
+
 #[derive(Clone, Copy, Debug, PartialEq, Format, Serialize, Deserialize)]
 pub struct Rule(u8);
 
@@ -16,59 +72,280 @@ impl Rule {
     pub fn inner(&self) -> u8 {
         self.0
     }
-}
 
-pub struct ElementaryCellularAutomaton {
-    pub wrap: Wrap,
-    pub rule: Rule,
-}
+    pub fn apply(&self, left: bool, center: bool, right: bool) -> bool {
+        let index = (left as u8) << 2 | (center as u8) << 1 | right as u8;
+        (self.0 >> index) & 1 == 1
+    }
 
-impl ElementaryCellularAutomaton {
-    pub fn new(wrapping: Wrap, rule: Rule) -> Self {
-        Self {
-            wrap: wrapping,
-            rule,
+    /// Evaluates the rule for every bit position of the given neighbourhood
+    /// words at once.
+    pub fn apply_word<T: Word>(&self, left: T, center: T, right: T) -> T {
+        let mut result = T::ZERO;
+        for index in 0..8 {
+            if (self.0 >> index) & 1 == 0 {
+                continue;
+            }
+            let left = if index & 0b100 != 0 { left } else { !left };
+            let center = if index & 0b010 != 0 { center } else { !center };
+            let right = if index & 0b001 != 0 { right } else { !right };
+            result = result | (left & center & right);
         }
+        result
     }
 
-    pub fn next(&self, state: &[bool], next_state: &mut [bool]) {
-        assert_eq!(state.len(), next_state.len());
+    fn map_neighbourhoods(&self, map: impl Fn(u8) -> u8) -> Self {
+        Self((0..8).fold(0, |rule, index| {
+            rule | ((self.0 >> map(index)) & 1) << index
+        }))
+    }
 
-        let len = state.len();
+    /// The rule with left and right swapped.
+    pub fn mirror(&self) -> Self {
+        self.map_neighbourhoods(|index| (index & 0b010) | (index >> 2) | (index & 0b001) << 2)
+    }
 
-        for i in 0..len {
-            let left = self.wrap.left(state, i);
-            let center = state[i];
-            let right = self.wrap.right(state, i);
+    /// The rule with live and dead cells swapped.
+    pub fn complement(&self) -> Self {
+        Self(!self.map_neighbourhoods(|index| 7 - index).0)
+    }
 
-            let index = (left as u8) << 2 | (center as u8) << 1 | right as u8;
-            next_state[i] = (self.rule.0 >> index) & 1 == 1;
-        }
+    pub fn mirror_complement(&self) -> Self {
+        self.mirror().complement()
     }
 
-    pub fn period<const W: usize, const MAX: usize>(&self, initial_state: &[bool]) -> Option<usize> {
-        assert_eq!(initial_state.len(), W);
+    /// The rules which behave identically up to reflection and/or swapping
+    /// live and dead cells.
+    pub fn equivalents(&self) -> [Rule; 4] {
+        [
+            *self,
+            self.mirror(),
+            self.complement(),
+            self.mirror_complement(),
+        ]
+    }
 
-        let mut state = [[false; W]; MAX];
-        state[0].copy_from_slice(initial_state);
+    /// The lowest numbered rule of the equivalence class.
+    pub fn canonical(&self) -> Self {
+        self.equivalents()
+            .into_iter()
+            .min_by_key(Rule::inner)
+            .unwrap()
+    }
 
-        for i in 1..MAX {
-            self.next_row(&mut state, i);
+    /// The canonical representatives of the 88 equivalence classes.
+    pub fn canonical_rules() -> impl Iterator<Item = Rule> {
+        (0..=255)
+            .map(Rule::new)
+            .filter(|rule| rule.canonical() == *rule)
+    }
+
+    pub fn is_symmetric(&self) -> bool {
+        self.mirror() == *self
+    }
+
+    /// Wolfram's "legal" rules, which are symmetric and keep an all dead row
+    /// dead.
+    pub fn is_legal(&self) -> bool {
+        self.is_symmetric() && !self.apply(false, false, false)
+    }
+
+    /// Returns `(left, center, right)` when the rule is the XOR of the
+    /// selected neighbours.
+    pub fn additive_coefficients(&self) -> Option<(bool, bool, bool)> {
+        let coefficients = (
+            self.apply(true, false, false),
+            self.apply(false, true, false),
+            self.apply(false, false, true),
+        );
+        let (a, b, c) = coefficients;
+        let additive = (0..8).all(|index| {
+            let (left, center, right) =
+                (index & 0b100 != 0, index & 0b010 != 0, index & 0b001 != 0);
+            self.apply(left, center, right) == (a & left) ^ (b & center) ^ (c & right)
+        });
+        additive.then_some(coefficients)
+    }
+
+    pub fn is_additive(&self) -> bool {
+        self.additive_coefficients().is_some()
+    }
+
+    /// Whether the number of live cells is preserved on a wrapping row, using
+    /// the Boccara–Fukś criterion.
+    pub fn is_number_conserving(&self) -> bool {
+        let f = |left, center, right| self.apply(left, center, right) as i8;
+        (0..8).all(|index| {
+            let (left, center, right) =
+                (index & 0b100 != 0, index & 0b010 != 0, index & 0b001 != 0);
+            f(left, center, right)
+                == left as i8 + f(false, center, right) - f(false, left, center)
+                    + f(false, false, center)
+                    - f(false, false, left)
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Format)]
+pub enum AutomatonError {
+    /// A state had a different number of cells to the one expected.
+    LengthMismatch { expected: usize, actual: usize },
+    /// A ring of rows had fewer than the three rows needed to step it.
+    TooFewRows(usize),
+    /// A row index was past the end of the ring.
+    IndexOutOfRange { index: usize, height: usize },
+    /// A cell index was past the end of the row.
+    CellOutOfRange { index: usize, width: usize },
+    /// A measurement was asked to run for no generations.
+    NoGenerations,
+}
+
+impl fmt::Display for AutomatonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LengthMismatch { expected, actual } => {
+                write!(f, "expected {} cells but found {}", expected, actual)
+            }
+            Self::TooFewRows(height) => write!(f, "expected at least 3 rows but found {}", height),
+            Self::IndexOutOfRange { index, height } => {
+                write!(f, "row {} is out of range for {} rows", index, height)
+            }
+            Self::CellOutOfRange { index, width } => {
+                write!(f, "cell {} is out of range for {} cells", index, width)
+            }
+            Self::NoGenerations => write!(f, "expected at least 1 generation"),
         }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AutomatonError {}
 
-        let last_index = MAX - 1;
-        for i in 1..MAX {
-            if state[last_index] == state[last_index - i] {
-                return Some(i);
+fn check_length(expected: usize, actual: usize) -> Result<(), AutomatonError> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(AutomatonError::LengthMismatch { expected, actual })
+    }
+}
+
+fn check_width<A: CellularAutomaton + ?Sized>(
+    automaton: &A,
+    actual: usize,
+) -> Result<(), AutomatonError> {
+    automaton
+        .width()
+        .map_or(Ok(()), |expected| check_length(expected, actual))
+}
+
+/// Brent's algorithm finds a cycle with `transient + period < MAX` in fewer
+/// than `3 * MAX` generations, so [`CellularAutomaton::period`] searches this
+/// many multiples of `MAX` to never miss one.
+pub const PERIOD_SEARCH_FACTOR: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Format)]
+pub struct Cycle {
+    /// Generations before the first state that belongs to the cycle.
+    pub transient: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// Finds the cycle reached from `initial` using Brent's algorithm, giving
+    /// up after `max_generations` generations.
+    pub(crate) fn find<S: Clone + PartialEq>(
+        initial: S,
+        step: impl Fn(&S) -> S,
+        max_generations: usize,
+    ) -> Option<Self> {
+        let mut power = 1;
+        let mut period = 1;
+        let mut generations = 1;
+        let mut tortoise = initial.clone();
+        let mut hare = step(&initial);
+        while tortoise != hare {
+            if generations >= max_generations {
+                return None;
             }
+            if power == period {
+                tortoise = hare.clone();
+                power *= 2;
+                period = 0;
+            }
+            hare = step(&hare);
+            period += 1;
+            generations += 1;
         }
 
-        None
+        let mut transient = 0;
+        tortoise = initial.clone();
+        hare = initial;
+        for _ in 0..period {
+            hare = step(&hare);
+        }
+        while tortoise != hare {
+            tortoise = step(&tortoise);
+            hare = step(&hare);
+            transient += 1;
+        }
+
+        Some(Cycle { transient, period })
+    }
+    /// Searches `PERIOD_SEARCH_FACTOR * max` generations with `find`, then
+    /// returns the period if the cycle is reached and repeated within the
+    /// first `max` generations.
+    pub(crate) fn period_within(
+        max: usize,
+        find: impl FnOnce(usize) -> Option<Self>,
+    ) -> Option<usize> {
+        find(PERIOD_SEARCH_FACTOR * max)
+            .filter(|cycle| cycle.transient + cycle.period < max)
+            .map(|cycle| cycle.period)
+    }
+}
+
+/// A one-dimensional automaton which computes each generation from the
+/// previous one.
+pub trait CellularAutomaton {
+    type Cell: Copy + Default + PartialEq;
+
+    fn next(&self, state: &[Self::Cell], next_state: &mut [Self::Cell]);
+
+    /// Finds the cycle reached from `initial_state` using Brent's algorithm,
+    /// giving up after `max_generations` generations.
+    fn cycle<const W: usize>(
+        &self,
+        initial_state: &[Self::Cell],
+        max_generations: usize,
+    ) -> Option<Cycle> {
+        assert_eq!(initial_state.len(), W);
+
+        let mut initial = [Self::Cell::default(); W];
+        initial.copy_from_slice(initial_state);
+        let step = |state: &[Self::Cell; W]| {
+            let mut next_state = [Self::Cell::default(); W];
+            self.next(state, &mut next_state);
+            next_state
+        };
+
+        Cycle::find(initial, step, max_generations)
+    }
+
+    /// Returns the period of the cycle if it is reached and repeated within
+    /// the first `MAX` generations, i.e. if generation `MAX - 1` equals an
+    /// earlier generation. This holds exactly when `transient + period < MAX`.
+    fn period<const W: usize, const MAX: usize>(
+        &self,
+        initial_state: &[Self::Cell],
+    ) -> Option<usize> {
+        Cycle::period_within(MAX, |generations| {
+            self.cycle::<W>(initial_state, generations)
+        })
     }
 
-    pub fn next_row<const W: usize, const H: usize>(
+    fn next_row<const W: usize, const H: usize>(
         &self,
-        state: &mut [[bool; W]; H],
+        state: &mut [[Self::Cell; W]; H],
         index: usize,
     ) {
         assert!(H > 2);
@@ -81,6 +358,171 @@ impl ElementaryCellularAutomaton {
         };
         self.next(previous_row, next_row);
     }
+
+    /// The only width this automaton can step, for automata such as
+    /// [`HybridCellularAutomaton`] with per-cell parameters.
+    fn width(&self) -> Option<usize> {
+        None
+    }
+
+    /// Like [`next`](Self::next), but returns an error for states of the wrong
+    /// length rather than panicking.
+    fn try_next(
+        &self,
+        state: &[Self::Cell],
+        next_state: &mut [Self::Cell],
+    ) -> Result<(), AutomatonError> {
+        check_length(state.len(), next_state.len())?;
+        check_width(self, state.len())?;
+        self.next(state, next_state);
+        Ok(())
+    }
+
+    fn try_cycle<const W: usize>(
+        &self,
+        initial_state: &[Self::Cell],
+        max_generations: usize,
+    ) -> Result<Option<Cycle>, AutomatonError> {
+        check_length(W, initial_state.len())?;
+        check_width(self, W)?;
+        Ok(self.cycle::<W>(initial_state, max_generations))
+    }
+
+    fn try_period<const W: usize, const MAX: usize>(
+        &self,
+        initial_state: &[Self::Cell],
+    ) -> Result<Option<usize>, AutomatonError> {
+        check_length(W, initial_state.len())?;
+        check_width(self, W)?;
+        Ok(self.period::<W, MAX>(initial_state))
+    }
+
+    fn try_next_row<const W: usize, const H: usize>(
+        &self,
+        state: &mut [[Self::Cell; W]; H],
+        index: usize,
+    ) -> Result<(), AutomatonError> {
+        if H < 3 {
+            return Err(AutomatonError::TooFewRows(H));
+        }
+        if index >= H {
+            return Err(AutomatonError::IndexOutOfRange { index, height: H });
+        }
+        check_width(self, W)?;
+        self.next_row(state, index);
+        Ok(())
+    }
+
+    /// Iterates over `initial` followed by each generation after it.
+    fn evolve<const W: usize>(&self, initial: [Self::Cell; W]) -> Evolve<'_, Self, W>
+    where
+        Self: Sized,
+    {
+        Evolve::new(self, initial)
+    }
+}
+
+pub struct ElementaryCellularAutomaton {
+    pub wrap: Wrap,
+    pub rule: Rule,
+}
+
+impl ElementaryCellularAutomaton {
+    pub fn new(wrapping: Wrap, rule: Rule) -> Self {
+        Self {
+            wrap: wrapping,
+            rule,
+        }
+    }
+
+    /// Computes the next generation of a `width` cell row packed into words.
+    pub fn next_words<T: Word>(&self, width: usize, state: &[T], next_state: &mut [T]) {
+        let count = bits::word_count::<T>(width);
+        assert_eq!(state.len(), count);
+        assert_eq!(next_state.len(), count);
+        self.next_with(width, |k| state[k], |k, word| next_state[k] = word);
+    }
+
+    pub fn next_packed<T: Word, const N: usize>(
+        &self,
+        state: &BitRow<T, N>,
+        next_state: &mut BitRow<T, N>,
+    ) {
+        assert_eq!(state.width(), next_state.width());
+        self.next_words(state.width(), state.words(), next_state.words_mut());
+    }
+
+    /// Steps a `width` cell row whose `k`th packed word is `word(k)`, passing
+    /// each word of the next generation to `write`. Bits beyond `width` are
+    /// ignored on input and zero on output.
+    fn next_with<T: Word>(
+        &self,
+        width: usize,
+        word: impl Fn(usize) -> T,
+        mut write: impl FnMut(usize, T),
+    ) {
+        if width == 0 {
+            return;
+        }
+
+        let last = bits::word_count::<T>(width) - 1;
+        let last_mask = T::last_mask(width);
+        let last_bit = (width - 1) as u32 % T::BITS;
+        let word = |k: usize| {
+            if k == last {
+                word(k) & last_mask
+            } else {
+                word(k)
+            }
+        };
+        let (left_edge, right_edge) = self.wrap.edges(word(0).bit(0), word(last).bit(last_bit));
+
+        for k in 0..=last {
+            let center = word(k);
+            let left_in = if k == 0 {
+                T::from_bit(left_edge)
+            } else {
+                word(k - 1) >> (T::BITS - 1)
+            };
+            let right_in = if k == last {
+                T::from_bit(right_edge) << last_bit
+            } else {
+                word(k + 1) << (T::BITS - 1)
+            };
+
+            let output =
+                self.rule
+                    .apply_word(center << 1 | left_in, center, center >> 1 | right_in);
+            let output = if k == last {
+                output & last_mask
+            } else {
+                output
+            };
+            write(k, output);
+        }
+    }
+}
+
+impl CellularAutomaton for ElementaryCellularAutomaton {
+    type Cell = bool;
+
+    /// Packs the row 64 cells at a time and steps it as words.
+    fn next(&self, state: &[bool], next_state: &mut [bool]) {
+        assert_eq!(state.len(), next_state.len());
+
+        let bits = u64::BITS as usize;
+        let word = |k: usize| {
+            let cells = state[k * bits..].iter().take(bits);
+            cells
+                .rev()
+                .fold(0u64, |word, &cell| word << 1 | cell as u64)
+        };
+        self.next_with(state.len(), word, |k, output| {
+            for (i, cell) in next_state[k * bits..].iter_mut().take(bits).enumerate() {
+                *cell = output.bit(i as u32);
+            }
+        });
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Format, Serialize, Deserialize)]
@@ -88,28 +530,104 @@ pub enum Wrap {
     Wrap,
     Zero,
     One,
+    /// Mirrors the row beyond each edge, so the edge cells are their own
+    /// neighbours.
+    Reflect,
+    /// Draws fresh edge cells from a xorshift generator, whose state is
+    /// stepped once per generation by `Wrap::next_generation`.
+    ///
+    /// Automata never step the generator themselves: `next`, `evolve`,
+    /// `cycle`, `period` and `advance` all see the same fixed edge cells, so
+    /// any cycle or period they report holds only for that fixed edge, not
+    /// for a row whose wrap is stepped every generation.
+    Random(u32),
+    /// Edge cells supplied by the caller each generation, e.g. from the
+    /// network or a button.
+    Input {
+        left: bool,
+        right: bool,
+    },
 }
 
 impl Wrap {
-    fn left(&self, state: &[bool], i: usize) -> bool {
-        if i > 0 {
-            return state[i - 1];
+    /// Replaces a zero seed, as xorshift never leaves the zero state.
+    const NONZERO_SEED: u32 = 0x9e37_79b9;
+
+    pub fn random(seed: u32) -> Self {
+        Wrap::Random(if seed == 0 { Self::NONZERO_SEED } else { seed })
+    }
+
+    /// Steps the xorshift generator behind [`Wrap::Random`], replacing a zero
+    /// seed as [`Wrap::random`] does.
+    pub fn next_seed(seed: u32) -> u32 {
+        let mut state = if seed == 0 { Self::NONZERO_SEED } else { seed };
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state
+    }
+
+    pub fn input(left: bool, right: bool) -> Self {
+        Wrap::Input { left, right }
+    }
+
+    /// Returns the boundary to use for the following generation.
+    pub fn next_generation(&self) -> Self {
+        match *self {
+            Wrap::Random(state) => Wrap::Random(Self::next_seed(state)),
+            wrap => wrap,
         }
+    }
+
+    /// Returns the cells beyond the left and right edges of a row with the
+    /// given first and last cells.
+    pub fn edges<T: Copy + From<bool>>(&self, first: T, last: T) -> (T, T) {
         match self {
-            Wrap::Wrap => state[state.len() - 1],
-            Wrap::Zero => false,
-            Wrap::One => true,
+            Wrap::Wrap => (last, first),
+            Wrap::Zero => (false.into(), false.into()),
+            Wrap::One => (true.into(), true.into()),
+            Wrap::Reflect => (first, last),
+            Wrap::Random(state) => ((state & 0b01 != 0).into(), (state & 0b10 != 0).into()),
+            Wrap::Input { left, right } => ((*left).into(), (*right).into()),
         }
     }
-    fn right(&self, state: &[bool], i: usize) -> bool {
-        if i < state.len() - 1 {
-            return state[i + 1];
+
+    /// Returns the cell at `i`, which may lie any distance beyond either edge
+    /// of the row.
+    pub fn cell<T: Copy + From<bool>>(&self, state: &[T], i: isize) -> T {
+        let len = state.len() as isize;
+        if (0..len).contains(&i) {
+            return state[i as usize];
         }
         match self {
-            Wrap::Wrap => state[0],
-            Wrap::Zero => false,
-            Wrap::One => true,
+            Wrap::Wrap => state[i.rem_euclid(len) as usize],
+            Wrap::Reflect => {
+                let mirrored = i.rem_euclid(2 * len);
+                state[mirrored.min(2 * len - 1 - mirrored) as usize]
+            }
+            _ => {
+                let (left, right) = self.edges(state[0], state[state.len() - 1]);
+                if i < 0 {
+                    left
+                } else {
+                    right
+                }
+            }
+        }
+    }
+
+    pub fn left<T: Copy + From<bool>>(&self, state: &[T], i: usize) -> T {
+        if i > 0 {
+            return state[i - 1];
         }
+        self.edges(state[0], state[state.len() - 1]).0
+    }
+
+    pub fn right<T: Copy + From<bool>>(&self, state: &[T], i: usize) -> T {
+        if i < state.len() - 1 {
+            return state[i + 1];
+        }
+        self.edges(state[0], state[state.len() - 1]).1
     }
 }
 
@@ -117,6 +635,102 @@ impl Wrap {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_rule_symmetries() {
+        let rule = Rule::new(30);
+        assert_eq!(rule.mirror(), Rule::new(86));
+        assert_eq!(rule.complement(), Rule::new(135));
+        assert_eq!(rule.mirror_complement(), Rule::new(149));
+        assert_eq!(Rule::new(149).canonical(), rule);
+        assert_eq!(Rule::new(137).canonical(), Rule::new(110));
+
+        assert_eq!(Rule::canonical_rules().count(), 88);
+        assert_eq!((0..=255).map(Rule::new).filter(Rule::is_legal).count(), 32);
+    }
+
+    #[test]
+    fn test_rule_predicates() {
+        let additive: [u8; 8] = [0, 60, 90, 102, 150, 170, 204, 240];
+        for rule in (0..=255).map(Rule::new) {
+            assert_eq!(rule.is_additive(), additive.contains(&rule.inner()));
+        }
+        assert_eq!(
+            Rule::new(150).additive_coefficients(),
+            Some((true, true, true))
+        );
+
+        // Check conservation by brute force over every wrapping row of 8 cells
+        for rule in (0..=255).map(Rule::new) {
+            let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, rule);
+            let conserving = (0..=255u8).all(|row| {
+                let state: [bool; 8] = core::array::from_fn(|i| (row >> i) & 1 == 1);
+                let mut next_state = [false; 8];
+                automaton.next(&state, &mut next_state);
+                state.iter().filter(|&&cell| cell).count()
+                    == next_state.iter().filter(|&&cell| cell).count()
+            });
+            assert_eq!(rule.is_number_conserving(), conserving, "{:?}", rule);
+        }
+    }
+
+    #[test]
+    fn test_wrap_boundaries() {
+        let state = [true, false, false, true, true];
+
+        assert_eq!(Wrap::Reflect.edges(true, false), (true, false));
+        let reflected = [-3, -1, 6, 7].map(|i| Wrap::Reflect.cell(&state, i));
+        assert_eq!(reflected, [false, true, true, false]);
+
+        let input = Wrap::input(true, false);
+        assert_eq!(
+            (input.left(&state, 0), input.right(&state, 4)),
+            (true, false)
+        );
+        assert_eq!(
+            Wrap::input(true, false).next_generation(),
+            Wrap::input(true, false)
+        );
+
+        let automaton = ElementaryCellularAutomaton::new(Wrap::input(true, false), Rule::new(204));
+        let mut next_state = [false; 5];
+        automaton.next(&state, &mut next_state);
+        assert_eq!(next_state, state);
+    }
+
+    #[test]
+    fn test_wrap_random() {
+        assert_eq!(Wrap::random(0), Wrap::random(0));
+        assert_ne!(Wrap::random(0), Wrap::Random(0));
+        assert_eq!(
+            Wrap::random(9).next_generation(),
+            Wrap::Random(Wrap::next_seed(9))
+        );
+        assert_eq!(Wrap::next_seed(0), Wrap::next_seed(Wrap::NONZERO_SEED));
+        assert_ne!(Wrap::Random(0).next_generation(), Wrap::Random(0));
+
+        // Every combination of edge cells is drawn over a few generations
+        let mut seen = [false; 4];
+        let mut wrap = Wrap::random(1);
+        for _ in 0..32 {
+            let (left, right) = wrap.edges(false, false);
+            seen[(left as usize) << 1 | right as usize] = true;
+            wrap = wrap.next_generation();
+        }
+        assert_eq!(seen, [true; 4]);
+
+        // Rule 170 shifts the row left, drawing in the right edge cell
+        let mut wrap = Wrap::random(7);
+        let mut state = [false; 8];
+        for _ in 0..8 {
+            let automaton = ElementaryCellularAutomaton::new(wrap, Rule::new(170));
+            let mut next_state = [false; 8];
+            automaton.next(&state, &mut next_state);
+            assert_eq!(next_state[7], wrap.edges(false, false).1);
+            state = next_state;
+            wrap = wrap.next_generation();
+        }
+    }
+
     #[test]
     fn test_elementary_cellular_automaton() {
         // https://mathworld.wolfram.com/Rule30.html
@@ -148,6 +762,57 @@ mod tests {
         assert_eq!(automaton.period::<8, 192>(&initial_state), Some(8));
     }
 
+    #[test]
+    fn test_elementary_cellular_automaton_period_window() {
+        // Matches simulating the first MAX generations and looking for an
+        // earlier copy of the last one
+        for rule in (0..=255).step_by(7).map(Rule::new) {
+            let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, rule);
+            for row in 0..=255u8 {
+                let mut state = [[false; 8]; 12];
+                state[0] = core::array::from_fn(|i| (row >> i) & 1 == 1);
+                for i in 1..12 {
+                    automaton.next_row(&mut state, i);
+                }
+                let expected = (1..12).find(|&i| state[11] == state[11 - i]);
+                assert_eq!(automaton.period::<8, 12>(&state[0]), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_elementary_cellular_automaton_cycle() {
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(2));
+        let initial_state = [false, false, false, false, false, false, false, true];
+
+        assert_eq!(
+            automaton.cycle::<8>(&initial_state, 192),
+            Some(Cycle {
+                transient: 0,
+                period: 8
+            })
+        );
+
+        // Rule 30 from a single cell on a ring of 11 cells
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(30));
+        let mut initial_state = [false; 11];
+        initial_state[5] = true;
+        let cycle = automaton.cycle::<11>(&initial_state, 1 << 11).unwrap();
+
+        let mut state = [[false; 11]; 192];
+        state[0] = initial_state;
+        for i in 1..192 {
+            automaton.next_row(&mut state, i);
+        }
+        let first_repeat = (1..192).find(|&i| state[..i].contains(&state[i])).unwrap();
+        assert_eq!(cycle.transient + cycle.period, first_repeat);
+        assert_eq!(state[cycle.transient], state[first_repeat]);
+        assert_eq!(
+            automaton.cycle::<11>(&initial_state, first_repeat / 2),
+            None
+        );
+    }
+
     #[test]
     fn test_elementary_cellular_automaton_next_row() {
         let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(2));
@@ -172,4 +837,124 @@ mod tests {
             [false, false, false, false, false, true, false, false]
         );
     }
+
+    fn reference_next(
+        automaton: &ElementaryCellularAutomaton,
+        state: &[bool],
+        next_state: &mut [bool],
+    ) {
+        for (i, cell) in next_state.iter_mut().enumerate() {
+            let left = automaton.wrap.left(state, i);
+            let right = automaton.wrap.right(state, i);
+            *cell = automaton.rule.apply(left, state[i], right);
+        }
+    }
+
+    #[test]
+    fn test_elementary_cellular_automaton_packed() {
+        let mut seed = 0x2545_f491u32;
+        let mut cells = [false; 70];
+        for cell in cells.iter_mut() {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            *cell = seed & 1 == 1;
+        }
+
+        for wrap in [
+            Wrap::Wrap,
+            Wrap::Zero,
+            Wrap::One,
+            Wrap::Reflect,
+            Wrap::random(5),
+        ] {
+            for rule in 0..=255 {
+                let automaton = ElementaryCellularAutomaton::new(wrap, Rule::new(rule));
+                for width in [1, 7, 8, 9, 64, 70] {
+                    let state = &cells[..width];
+                    let mut expected = [false; 70];
+                    reference_next(&automaton, state, &mut expected[..width]);
+
+                    let mut next_state = [false; 70];
+                    automaton.next(state, &mut next_state[..width]);
+                    assert_eq!(next_state[..width], expected[..width]);
+
+                    let packed = BitRow::<u8, 9>::from_bools(state);
+                    let mut next_packed = BitRow::<u8, 9>::new(width);
+                    automaton.next_packed(&packed, &mut next_packed);
+                    assert_eq!(next_packed, BitRow::from_bools(&expected[..width]));
+
+                    let packed = BitRow::<u64, 2>::from_bools(state);
+                    let mut next_packed = BitRow::<u64, 2>::new(width);
+                    automaton.next_packed(&packed, &mut next_packed);
+                    assert_eq!(next_packed, BitRow::from_bools(&expected[..width]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fallible_automaton() {
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(30));
+        let state = [false, true, false, false];
+
+        assert_eq!(
+            automaton.try_next(&state, &mut [false; 3]),
+            Err(AutomatonError::LengthMismatch {
+                expected: 4,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            automaton.try_period::<5, 16>(&state),
+            Err(AutomatonError::LengthMismatch {
+                expected: 5,
+                actual: 4
+            })
+        );
+        assert_eq!(
+            automaton.try_period::<4, 16>(&state),
+            Ok(automaton.period::<4, 16>(&state))
+        );
+
+        let mut rows = [state; 2];
+        assert_eq!(
+            automaton.try_next_row(&mut rows, 0),
+            Err(AutomatonError::TooFewRows(2))
+        );
+        let mut rows = [state; 3];
+        assert_eq!(
+            automaton.try_next_row(&mut rows, 3),
+            Err(AutomatonError::IndexOutOfRange {
+                index: 3,
+                height: 3
+            })
+        );
+        assert_eq!(automaton.try_next_row(&mut rows, 0), Ok(()));
+        assert_eq!(rows[0], [true, true, true, false]);
+
+        let hybrid = HybridCellularAutomaton::new(Wrap::Wrap, [Rule::new(30); 4]);
+        assert_eq!(
+            hybrid.try_next(&[false; 5], &mut [false; 5]),
+            Err(AutomatonError::LengthMismatch {
+                expected: 4,
+                actual: 5
+            })
+        );
+        assert_eq!(
+            hybrid.try_cycle::<5>(&[false; 5], 64),
+            Err(AutomatonError::LengthMismatch {
+                expected: 4,
+                actual: 5
+            })
+        );
+        let mut rows = [[false; 5]; 3];
+        assert_eq!(
+            hybrid.try_next_row(&mut rows, 1),
+            Err(AutomatonError::LengthMismatch {
+                expected: 4,
+                actual: 5
+            })
+        );
+    }
 }
@@ -0,0 +1,187 @@
+use defmt::Format;
+use serde::{Deserialize, Serialize};
+
+use crate::{mobius, ParseRuleError};
+
+/// A Life-like "B/S" rule, where bit `n` of `birth` and `survival` is set if
+/// a dead or live cell with `n` live neighbours is live next generation.
+#[derive(Clone, Copy, Debug, PartialEq, Format, Serialize, Deserialize)]
+#[serde(try_from = "RawLifeRule")]
+pub struct LifeRule {
+    birth: u16,
+    survival: u16,
+}
+
+/// Unchecked form of [`LifeRule`], validated on deserialisation.
+#[derive(Deserialize)]
+struct RawLifeRule {
+    birth: u16,
+    survival: u16,
+}
+
+impl TryFrom<RawLifeRule> for LifeRule {
+    type Error = ParseRuleError;
+
+    /// Rejects rules with bits set for more than eight neighbours.
+    fn try_from(raw: RawLifeRule) -> Result<Self, Self::Error> {
+        if Self::is_valid(raw.birth, raw.survival) {
+            Ok(Self {
+                birth: raw.birth,
+                survival: raw.survival,
+            })
+        } else {
+            Err(ParseRuleError::OutOfRange)
+        }
+    }
+}
+
+impl LifeRule {
+    /// B3/S23
+    pub const CONWAY: Self = Self::new(1 << 3, 1 << 2 | 1 << 3);
+    /// B36/S23
+    pub const HIGHLIFE: Self = Self::new(1 << 3 | 1 << 6, 1 << 2 | 1 << 3);
+    /// B2/S
+    pub const SEEDS: Self = Self::new(1 << 2, 0);
+
+    pub const fn new(birth: u16, survival: u16) -> Self {
+        assert!(Self::is_valid(birth, survival));
+        Self { birth, survival }
+    }
+
+    const fn is_valid(birth: u16, survival: u16) -> bool {
+        birth >> 9 == 0 && survival >> 9 == 0
+    }
+
+    pub fn birth(&self) -> u16 {
+        self.birth
+    }
+
+    pub fn survival(&self) -> u16 {
+        self.survival
+    }
+
+    pub fn apply(&self, alive: bool, neighbours: u8) -> bool {
+        let mask = if alive { self.survival } else { self.birth };
+        (mask >> neighbours) & 1 == 1
+    }
+}
+
+/// A Life-like automaton on a grid of `L` rows of `W` cells glued into a
+/// Möbius strip, matching the display's `[[bool; WIDTH]; HEIGHT]` universe.
+/// Cells beyond the strip's edges are dead.
+pub struct LifeLikeAutomaton {
+    pub rule: LifeRule,
+}
+
+impl LifeLikeAutomaton {
+    pub fn new(rule: LifeRule) -> Self {
+        Self { rule }
+    }
+
+    pub fn neighbours<const W: usize, const L: usize>(
+        state: &[[bool; W]; L],
+        row: usize,
+        cell: usize,
+    ) -> u8 {
+        let mut neighbours = 0;
+        for row_offset in -1..=1 {
+            for cell_offset in -1..=1 {
+                if row_offset == 0 && cell_offset == 0 {
+                    continue;
+                }
+                let position =
+                    mobius::glue::<W, L>(row as isize + row_offset, cell as isize + cell_offset);
+                if let Some(position) = position {
+                    neighbours += state[position.row][position.cell] as u8;
+                }
+            }
+        }
+        neighbours
+    }
+
+    pub fn next<const W: usize, const L: usize>(
+        &self,
+        state: &[[bool; W]; L],
+        next_state: &mut [[bool; W]; L],
+    ) {
+        for (row, next_row) in next_state.iter_mut().enumerate() {
+            for (cell, next_cell) in next_row.iter_mut().enumerate() {
+                let neighbours = Self::neighbours(state, row, cell);
+                *next_cell = self.rule.apply(state[row][cell], neighbours);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn live_cells<const W: usize, const L: usize>(state: &[[bool; W]; L]) -> [(usize, usize); 3] {
+        let mut cells = [(0, 0); 3];
+        let mut count = 0;
+        for (row, cells_in_row) in state.iter().enumerate() {
+            for (cell, &alive) in cells_in_row.iter().enumerate() {
+                if alive {
+                    cells[count] = (row, cell);
+                    count += 1;
+                }
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn test_life_blinker() {
+        let automaton = LifeLikeAutomaton::new(LifeRule::CONWAY);
+        let mut state = [[false; 8]; 10];
+        state[4][2] = true;
+        state[4][3] = true;
+        state[4][4] = true;
+        let mut next_state = [[false; 8]; 10];
+
+        automaton.next(&state, &mut next_state);
+        assert_eq!(live_cells(&next_state), [(3, 3), (4, 3), (5, 3)]);
+
+        automaton.next(&next_state, &mut state);
+        assert_eq!(live_cells(&state), [(4, 2), (4, 3), (4, 4)]);
+    }
+
+    #[test]
+    fn test_life_blinker_across_twist() {
+        // A line of three cells along the strip, straight through the twist
+        let automaton = LifeLikeAutomaton::new(LifeRule::CONWAY);
+        let mut state = [[false; 8]; 10];
+        state[9][3] = true;
+        state[0][4] = true;
+        state[1][4] = true;
+        let mut next_state = [[false; 8]; 10];
+
+        automaton.next(&state, &mut next_state);
+        assert_eq!(live_cells(&next_state), [(0, 3), (0, 4), (0, 5)]);
+
+        automaton.next(&next_state, &mut state);
+        assert_eq!(live_cells(&state), [(0, 4), (1, 4), (9, 3)]);
+    }
+
+    #[test]
+    fn test_life_rule() {
+        assert!(LifeRule::HIGHLIFE.apply(false, 6));
+        assert!(!LifeRule::CONWAY.apply(false, 6));
+        assert!(!LifeRule::SEEDS.apply(true, 2));
+        assert!(LifeRule::SEEDS.apply(false, 2));
+    }
+
+    #[test]
+    fn test_life_rule_deserialise() {
+        let rule: LifeRule = serde_json::from_str(r#"{"birth":8,"survival":12}"#).unwrap();
+        assert_eq!(rule, LifeRule::CONWAY);
+
+        for json in [
+            r#"{"birth":512,"survival":12}"#,
+            r#"{"birth":8,"survival":1024}"#,
+        ] {
+            assert!(serde_json::from_str::<LifeRule>(json).is_err());
+        }
+    }
+}
@@ -0,0 +1,27 @@
+/// Base 2 logarithm of a positive number, accurate to around 1e-6 without
+/// needing `std`.
+pub fn log2(x: f32) -> f32 {
+    let bits = x.to_bits();
+    let exponent = ((bits >> 23) & 0xff) as i32 - 127;
+    let mantissa = f32::from_bits((bits & 0x007f_ffff) | 0x3f80_0000);
+
+    // ln(m) = 2 atanh((m - 1) / (m + 1)) converges quickly for m in [1, 2)
+    let z = (mantissa - 1.0) / (mantissa + 1.0);
+    let z2 = z * z;
+    let ln = 2.0 * z * (1.0 + z2 * (1.0 / 3.0 + z2 * (1.0 / 5.0 + z2 * (1.0 / 7.0 + z2 / 9.0))));
+    exponent as f32 + ln * core::f32::consts::LOG2_E
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+
+    #[test]
+    fn test_log2() {
+        for x in [1.0, 1.5, 2.0, 3.0, 10.0, 0.1, 1e6] {
+            assert!((log2(x) - f32::log2(x)).abs() < 1e-5);
+        }
+    }
+}
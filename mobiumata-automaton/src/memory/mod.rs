@@ -0,0 +1,126 @@
+use defmt::Format;
+use serde::{Deserialize, Serialize};
+
+use crate::{CellularAutomaton, ElementaryCellularAutomaton, Rule, Wrap};
+
+/// How a cell's recent states are combined into the state seen by the rule.
+#[derive(Clone, Copy, Debug, PartialEq, Format, Serialize, Deserialize)]
+pub enum MemoryFunction {
+    /// The most common recent state, ties going to the most recent state.
+    Majority,
+    /// Whether the cell was alive an odd number of times.
+    Parity,
+}
+
+/// Elementary automaton with memory, where the rule is applied to a function
+/// of each cell's last `depth` states rather than its current state.
+pub struct MemoryCellularAutomaton {
+    pub wrap: Wrap,
+    pub rule: Rule,
+    pub depth: usize,
+    pub function: MemoryFunction,
+}
+
+impl MemoryCellularAutomaton {
+    pub fn new(wrapping: Wrap, rule: Rule, depth: usize, function: MemoryFunction) -> Self {
+        assert!(depth > 0);
+        Self {
+            wrap: wrapping,
+            rule,
+            depth,
+            function,
+        }
+    }
+
+    /// Combines the last `depth` generations, where `row(0)` is the most recent.
+    fn effective<'a, const W: usize>(&self, row: impl Fn(usize) -> &'a [bool; W]) -> [bool; W] {
+        core::array::from_fn(|i| {
+            let alive = (0..self.depth).filter(|&age| row(age)[i]).count();
+            match self.function {
+                MemoryFunction::Majority if 2 * alive == self.depth => row(0)[i],
+                MemoryFunction::Majority => 2 * alive > self.depth,
+                MemoryFunction::Parity => alive % 2 == 1,
+            }
+        })
+    }
+
+    /// Computes the next generation from `history`, most recent generation
+    /// first.
+    pub fn next<const W: usize>(&self, history: &[[bool; W]], next_state: &mut [bool; W]) {
+        assert!(history.len() >= self.depth);
+        let effective = self.effective(|age| &history[age]);
+        ElementaryCellularAutomaton::new(self.wrap, self.rule).next(&effective, next_state);
+    }
+
+    /// Computes the row at `index` from the `depth` rows before it in the ring.
+    pub fn next_row<const W: usize, const H: usize>(
+        &self,
+        state: &mut [[bool; W]; H],
+        index: usize,
+    ) {
+        assert!(H > 2);
+        assert!(self.depth < H);
+        let effective = self.effective(|age| &state[(index + H - 1 - age) % H]);
+        ElementaryCellularAutomaton::new(self.wrap, self.rule).next(&effective, &mut state[index]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_depth_one_matches_elementary() {
+        let state = [false, true, true, false, true, false, false, true];
+        let elementary = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(30));
+        let mut expected = [false; 8];
+        elementary.next(&state, &mut expected);
+
+        for function in [MemoryFunction::Majority, MemoryFunction::Parity] {
+            let automaton = MemoryCellularAutomaton::new(Wrap::Wrap, Rule::new(30), 1, function);
+            let mut next_state = [false; 8];
+            automaton.next(&[state], &mut next_state);
+            assert_eq!(next_state, expected);
+        }
+    }
+
+    #[test]
+    fn test_memory_functions() {
+        // Rule 204 is the identity, so the output is the effective state
+        let history = [
+            [true, true, false, false, true],
+            [true, false, true, false, false],
+            [false, true, true, false, true],
+            [true, false, false, true, false],
+        ];
+        let mut next_state = [false; 5];
+
+        let majority =
+            MemoryCellularAutomaton::new(Wrap::Wrap, Rule::new(204), 3, MemoryFunction::Majority);
+        majority.next(&history, &mut next_state);
+        assert_eq!(next_state, [true, true, true, false, true]);
+
+        let tie =
+            MemoryCellularAutomaton::new(Wrap::Wrap, Rule::new(204), 4, MemoryFunction::Majority);
+        tie.next(&history, &mut next_state);
+        assert_eq!(next_state, [true, true, false, false, true]);
+
+        let parity =
+            MemoryCellularAutomaton::new(Wrap::Wrap, Rule::new(204), 3, MemoryFunction::Parity);
+        parity.next(&history, &mut next_state);
+        assert_eq!(next_state, [false, false, false, false, false]);
+    }
+
+    #[test]
+    fn test_memory_next_row_wraps_history() {
+        let automaton =
+            MemoryCellularAutomaton::new(Wrap::Wrap, Rule::new(204), 2, MemoryFunction::Parity);
+        let mut state = [[false; 4]; 4];
+        state[3] = [true, true, false, false];
+        state[2] = [true, false, true, false];
+
+        automaton.next_row(&mut state, 0);
+
+        assert_eq!(state[0], [false, true, true, false]);
+    }
+}
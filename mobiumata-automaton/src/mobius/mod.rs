@@ -0,0 +1,68 @@
+/// A cell of a `L` row long, `W` cell wide grid glued into a Möbius strip.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MobiusPosition {
+    pub row: usize,
+    pub cell: usize,
+    /// Whether reaching the position crossed the half-twist, mirroring the
+    /// grid across its width.
+    pub flipped: bool,
+}
+
+/// Maps a position which may lie beyond either end of the strip back onto it.
+///
+/// Leaving either end at cell `c` re-enters the other end at cell
+/// `W - 1 - c`. Positions beyond the strip's edge, where `cell` is outside
+/// `0..W`, don't exist and return `None`.
+pub fn glue<const W: usize, const L: usize>(row: isize, cell: isize) -> Option<MobiusPosition> {
+    if !(0..W as isize).contains(&cell) {
+        return None;
+    }
+    let laps = row.div_euclid(L as isize);
+    let flipped = laps % 2 != 0;
+    let cell = if flipped {
+        W - 1 - cell as usize
+    } else {
+        cell as usize
+    };
+    Some(MobiusPosition {
+        row: row.rem_euclid(L as isize) as usize,
+        cell,
+        flipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glue() {
+        assert_eq!(
+            glue::<8, 192>(3, 2),
+            Some(MobiusPosition {
+                row: 3,
+                cell: 2,
+                flipped: false
+            })
+        );
+        assert_eq!(
+            glue::<8, 192>(192, 2),
+            Some(MobiusPosition {
+                row: 0,
+                cell: 5,
+                flipped: true
+            })
+        );
+        assert_eq!(
+            glue::<8, 192>(-1, 0),
+            Some(MobiusPosition {
+                row: 191,
+                cell: 7,
+                flipped: true
+            })
+        );
+        assert_eq!(glue::<8, 192>(384, 2).map(|p| p.cell), Some(2));
+        assert_eq!(glue::<8, 192>(10, 8), None);
+        assert_eq!(glue::<8, 192>(10, -1), None);
+    }
+}
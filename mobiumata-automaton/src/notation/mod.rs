@@ -0,0 +1,341 @@
+use core::fmt::{self, Display, Formatter};
+use core::str::FromStr;
+
+use defmt::Format;
+
+use crate::colour::ColourRuleKind;
+use crate::turmite::Turn;
+use crate::{ColourRule, LifeRule, RangeRule, Rule, TurmiteRule};
+
+/// Error parsing one of the rule notations:
+///
+/// - elementary rules as Wolfram numbers, `30` or `W30`
+/// - range-r rules as a radius and hex code, `R2,W6A`
+/// - colour rules as a colour count and decimal code, `K3,T777` for
+///   totalistic rules and `K3,G<code>` for general ones
+/// - Life-like rules as birth and survival counts, `B3/S23`
+/// - turmites as a turn per colour, `RL`
+#[derive(Clone, Copy, Debug, PartialEq, Format)]
+pub enum ParseRuleError {
+    Empty,
+    /// The character at this byte offset is not valid at that point.
+    UnexpectedCharacter(usize),
+    /// The text ended where more was expected.
+    UnexpectedEnd,
+    /// The rule number or one of its parameters is too large.
+    OutOfRange,
+}
+
+impl Display for ParseRuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty rule"),
+            Self::UnexpectedCharacter(position) => {
+                write!(f, "unexpected character at position {}", position)
+            }
+            Self::UnexpectedEnd => write!(f, "unexpected end of rule"),
+            Self::OutOfRange => write!(f, "rule out of range"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseRuleError {}
+
+/// Reads a rule notation from left to right, tracking the position for errors.
+struct Cursor<'a> {
+    text: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(text: &'a str) -> Result<Self, ParseRuleError> {
+        if text.is_empty() {
+            return Err(ParseRuleError::Empty);
+        }
+        Ok(Self {
+            text: text.as_bytes(),
+            position: 0,
+        })
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.position).map(u8::to_ascii_uppercase)
+    }
+
+    fn error(&self) -> ParseRuleError {
+        match self.peek() {
+            Some(_) => ParseRuleError::UnexpectedCharacter(self.position),
+            None => ParseRuleError::UnexpectedEnd,
+        }
+    }
+
+    /// Skips `prefix` if it is next, ignoring case.
+    fn optional(&mut self, prefix: u8) -> bool {
+        let found = self.peek() == Some(prefix);
+        self.position += found as usize;
+        found
+    }
+
+    fn expect(&mut self, prefix: u8) -> Result<(), ParseRuleError> {
+        if self.optional(prefix) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn number(&mut self, radix: u32) -> Result<u128, ParseRuleError> {
+        let start = self.position;
+        let mut number: u128 = 0;
+        while let Some(digit) = self.peek().and_then(|c| (c as char).to_digit(radix)) {
+            number = number
+                .checked_mul(radix as u128)
+                .and_then(|number| number.checked_add(digit as u128))
+                .ok_or(ParseRuleError::OutOfRange)?;
+            self.position += 1;
+        }
+        if self.position > start {
+            Ok(number)
+        } else {
+            Err(self.error())
+        }
+    }
+
+    /// Reads a run of neighbour counts as a bitmask, e.g. `23` as bits 2 and 3.
+    fn counts(&mut self) -> u16 {
+        let mut counts = 0;
+        while let Some(c @ b'0'..=b'8') = self.peek() {
+            counts |= 1 << (c - b'0');
+            self.position += 1;
+        }
+        counts
+    }
+
+    fn end(&self) -> Result<(), ParseRuleError> {
+        match self.peek() {
+            Some(_) => Err(self.error()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s)?;
+        cursor.optional(b'W');
+        let number = cursor.number(10)?;
+        cursor.end()?;
+        u8::try_from(number)
+            .map(Rule::new)
+            .map_err(|_| ParseRuleError::OutOfRange)
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inner())
+    }
+}
+
+impl FromStr for RangeRule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s)?;
+        cursor.expect(b'R')?;
+        let radius = cursor.number(10)?;
+        cursor.expect(b',')?;
+        cursor.expect(b'W')?;
+        let number = cursor.number(16)?;
+        cursor.end()?;
+
+        if !(1..=RangeRule::MAX_RADIUS as u128).contains(&radius) {
+            return Err(ParseRuleError::OutOfRange);
+        }
+        let neighbourhoods = 1u32 << (2 * radius + 1);
+        if neighbourhoods < u128::BITS && number >> neighbourhoods != 0 {
+            return Err(ParseRuleError::OutOfRange);
+        }
+        Ok(RangeRule::new(radius as u8, number))
+    }
+}
+
+impl Display for RangeRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "R{},W{:X}", self.radius(), self.inner())
+    }
+}
+
+impl FromStr for ColourRule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s)?;
+        cursor.expect(b'K')?;
+        let colours = cursor.number(10)?;
+        cursor.expect(b',')?;
+        let kind = match cursor.peek() {
+            Some(b'T') => ColourRuleKind::Totalistic,
+            Some(b'G') => ColourRuleKind::General,
+            _ => return Err(cursor.error()),
+        };
+        cursor.position += 1;
+        let code = cursor.number(10)?;
+        cursor.end()?;
+
+        let colours = u8::try_from(colours).map_err(|_| ParseRuleError::OutOfRange)?;
+        ColourRule::try_new(kind, colours, code)
+    }
+}
+
+impl Display for ColourRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind() {
+            ColourRuleKind::General => 'G',
+            ColourRuleKind::Totalistic => 'T',
+        };
+        write!(f, "K{},{}{}", self.colours(), kind, self.code())
+    }
+}
+
+impl FromStr for LifeRule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s)?;
+        cursor.expect(b'B')?;
+        let birth = cursor.counts();
+        cursor.expect(b'/')?;
+        cursor.expect(b'S')?;
+        let survival = cursor.counts();
+        cursor.end()?;
+        Ok(LifeRule::new(birth, survival))
+    }
+}
+
+impl Display for LifeRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let counts = |f: &mut Formatter<'_>, mask: u16| {
+            (0..=8)
+                .filter(|n| (mask >> n) & 1 == 1)
+                .try_for_each(|n| write!(f, "{}", n))
+        };
+        write!(f, "B")?;
+        counts(f, self.birth())?;
+        write!(f, "/S")?;
+        counts(f, self.survival())
+    }
+}
+
+impl FromStr for TurmiteRule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s)?;
+        let mut turns = [Turn::None; TurmiteRule::MAX_COLOURS as usize];
+        let mut colours = 0;
+        while let Some(c) = cursor.peek() {
+            let turn = match c {
+                b'L' => Turn::Left,
+                b'R' => Turn::Right,
+                b'N' => Turn::None,
+                b'U' => Turn::UTurn,
+                _ => return Err(cursor.error()),
+            };
+            *turns.get_mut(colours).ok_or(ParseRuleError::OutOfRange)? = turn;
+            colours += 1;
+            cursor.position += 1;
+        }
+        Ok(TurmiteRule::new(&turns[..colours]))
+    }
+}
+
+impl Display for TurmiteRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        (0..self.colours()).try_for_each(|colour| {
+            let turn = match self.turn(colour) {
+                Turn::Left => 'L',
+                Turn::Right => 'R',
+                Turn::None => 'N',
+                Turn::UTurn => 'U',
+            };
+            write!(f, "{}", turn)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn test_rule_notation() {
+        assert_eq!("30".parse(), Ok(Rule::new(30)));
+        assert_eq!("W110".parse(), Ok(Rule::new(110)));
+        assert_eq!("w90".parse(), Ok(Rule::new(90)));
+        assert_eq!(Rule::new(30).to_string(), "30");
+
+        assert_eq!("".parse::<Rule>(), Err(ParseRuleError::Empty));
+        assert_eq!("W".parse::<Rule>(), Err(ParseRuleError::UnexpectedEnd));
+        assert_eq!(
+            "W3x".parse::<Rule>(),
+            Err(ParseRuleError::UnexpectedCharacter(2))
+        );
+        assert_eq!("256".parse::<Rule>(), Err(ParseRuleError::OutOfRange));
+    }
+
+    #[test]
+    fn test_range_and_colour_notation() {
+        let rule: RangeRule = "R2,W6A".parse().unwrap();
+        assert_eq!(rule, RangeRule::radius_2(0x6a));
+        assert_eq!(rule.to_string(), "R2,W6A");
+        assert_eq!("R1,W1E".parse(), Ok(RangeRule::from(Rule::new(30))));
+        assert_eq!(
+            "R1,W100".parse::<RangeRule>(),
+            Err(ParseRuleError::OutOfRange)
+        );
+        assert_eq!(
+            "R4,W0".parse::<RangeRule>(),
+            Err(ParseRuleError::OutOfRange)
+        );
+
+        let rule: ColourRule = "K3,T777".parse().unwrap();
+        assert_eq!(rule, ColourRule::totalistic(3, 777));
+        assert_eq!(rule.to_string(), "K3,T777");
+        assert_eq!("k2,g30".parse(), Ok(ColourRule::general(2, 30)));
+        assert_eq!(
+            "K3,T2187".parse::<ColourRule>(),
+            Err(ParseRuleError::OutOfRange)
+        );
+        assert_eq!(
+            "K3,X1".parse::<ColourRule>(),
+            Err(ParseRuleError::UnexpectedCharacter(3))
+        );
+    }
+
+    #[test]
+    fn test_life_and_turmite_notation() {
+        assert_eq!("B3/S23".parse(), Ok(LifeRule::CONWAY));
+        assert_eq!("b36/s23".parse(), Ok(LifeRule::HIGHLIFE));
+        assert_eq!("B2/S".parse(), Ok(LifeRule::SEEDS));
+        assert_eq!(LifeRule::HIGHLIFE.to_string(), "B36/S23");
+        assert_eq!(
+            "B3/S29".parse::<LifeRule>(),
+            Err(ParseRuleError::UnexpectedCharacter(5))
+        );
+
+        assert_eq!("RL".parse(), Ok(TurmiteRule::LANGTONS_ANT));
+        assert_eq!(TurmiteRule::parse("llrU").unwrap().to_string(), "LLRU");
+        assert_eq!(
+            "RLRLRLRLRLRLRLRLR".parse::<TurmiteRule>(),
+            Err(ParseRuleError::OutOfRange)
+        );
+    }
+}
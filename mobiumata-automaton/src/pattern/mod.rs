@@ -0,0 +1,418 @@
+use core::fmt::{self, Write};
+
+use defmt::Format;
+
+use crate::LifeRule;
+
+/// Longest line written in RLE output, as recommended by Golly.
+const RLE_LINE_LENGTH: usize = 70;
+
+#[derive(Clone, Copy, Debug, PartialEq, Format)]
+pub enum PatternError {
+    /// The byte at this offset is not valid at that point.
+    UnexpectedCharacter(usize),
+    /// An RLE pattern did not start with an `x = .., y = ..` header line.
+    MissingHeader,
+    /// A live cell fell outside the canvas.
+    OutOfBounds { row: usize, column: usize },
+    /// A run count, or the position it moves to, at this byte offset is too
+    /// large to represent.
+    Overflow(usize),
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedCharacter(offset) => {
+                write!(f, "unexpected character at offset {}", offset)
+            }
+            Self::MissingHeader => write!(f, "missing RLE header"),
+            Self::OutOfBounds { row, column } => {
+                write!(f, "cell at row {} column {} is out of bounds", row, column)
+            }
+            Self::Overflow(offset) => write!(f, "run count at offset {} is too large", offset),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PatternError {}
+
+/// Dimensions of a pattern, and its rule where an RLE header gives a Life-like
+/// one.
+#[derive(Clone, Copy, Debug, PartialEq, Format)]
+pub struct Header {
+    pub width: usize,
+    pub height: usize,
+    pub rule: Option<LifeRule>,
+}
+
+/// Cells a pattern can be read into.
+pub trait Canvas {
+    /// Sets the cell live, returning false if it is outside the canvas.
+    fn set(&mut self, row: usize, column: usize) -> bool;
+}
+
+impl Canvas for [bool] {
+    fn set(&mut self, row: usize, column: usize) -> bool {
+        match self.get_mut(column) {
+            Some(cell) if row == 0 => {
+                *cell = true;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<const W: usize> Canvas for [[bool; W]] {
+    fn set(&mut self, row: usize, column: usize) -> bool {
+        match self.get_mut(row).and_then(|row| row.get_mut(column)) {
+            Some(cell) => {
+                *cell = true;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn set<C: Canvas + ?Sized>(canvas: &mut C, row: usize, column: usize) -> Result<(), PatternError> {
+    if canvas.set(row, column) {
+        Ok(())
+    } else {
+        Err(PatternError::OutOfBounds { row, column })
+    }
+}
+
+/// Writes rows in the plaintext (`.cells`) format, omitting trailing dead
+/// cells.
+pub fn write_plaintext<W: Write, R: AsRef<[bool]>>(
+    out: &mut W,
+    name: Option<&str>,
+    rows: impl IntoIterator<Item = R>,
+) -> fmt::Result {
+    if let Some(name) = name {
+        writeln!(out, "!Name: {}", name)?;
+    }
+    for row in rows {
+        let row = row.as_ref();
+        let width = row.iter().rposition(|&cell| cell).map_or(0, |i| i + 1);
+        for &cell in &row[..width] {
+            out.write_char(if cell { 'O' } else { '.' })?;
+        }
+        out.write_char('\n')?;
+    }
+    Ok(())
+}
+
+/// Reads a plaintext (`.cells`) pattern, setting its live cells on `canvas`.
+pub fn read_plaintext<C: Canvas + ?Sized>(
+    input: impl IntoIterator<Item = u8>,
+    canvas: &mut C,
+) -> Result<Header, PatternError> {
+    let mut header = Header {
+        width: 0,
+        height: 0,
+        rule: None,
+    };
+    let (mut row, mut column) = (0, 0);
+    let mut comment = false;
+    for (offset, byte) in input.into_iter().enumerate() {
+        match byte {
+            b'\n' => {
+                if !comment {
+                    row += 1;
+                    header.height = row;
+                }
+                column = 0;
+                comment = false;
+            }
+            _ if comment => {}
+            b'!' if column == 0 => comment = true,
+            b'\r' => {}
+            b'.' | b'O' | b'*' => {
+                if byte != b'.' {
+                    set(canvas, row, column)?;
+                }
+                column += 1;
+                header.width = header.width.max(column);
+                header.height = row + 1;
+            }
+            _ => return Err(PatternError::UnexpectedCharacter(offset)),
+        }
+    }
+    Ok(header)
+}
+
+/// Streams rows out in Golly's run length encoded format.
+pub struct RleWriter<'a, W: Write> {
+    out: &'a mut W,
+    line_length: usize,
+    rows: usize,
+    pending_rows: usize,
+}
+
+impl<'a, W: Write> RleWriter<'a, W> {
+    /// Writes the header for a `width` by `height` pattern.
+    pub fn new(
+        out: &'a mut W,
+        width: usize,
+        height: usize,
+        rule: Option<LifeRule>,
+    ) -> Result<Self, fmt::Error> {
+        write!(out, "x = {}, y = {}", width, height)?;
+        if let Some(rule) = rule {
+            write!(out, ", rule = {}", rule)?;
+        }
+        out.write_char('\n')?;
+        Ok(Self {
+            out,
+            line_length: 0,
+            rows: 0,
+            pending_rows: 0,
+        })
+    }
+
+    /// Writes a run of `count` tags, wrapping long lines.
+    fn run(&mut self, count: usize, tag: char) -> fmt::Result {
+        let length = if count > 1 {
+            count.ilog10() as usize + 2
+        } else {
+            1
+        };
+        if self.line_length + length > RLE_LINE_LENGTH {
+            self.out.write_char('\n')?;
+            self.line_length = 0;
+        }
+        if count > 1 {
+            write!(self.out, "{}", count)?;
+        }
+        self.out.write_char(tag)?;
+        self.line_length += length;
+        Ok(())
+    }
+
+    pub fn row(&mut self, row: &[bool]) -> fmt::Result {
+        if self.rows > 0 {
+            self.pending_rows += 1;
+        }
+        self.rows += 1;
+        if !row.contains(&true) {
+            return Ok(());
+        }
+        if self.pending_rows > 0 {
+            self.run(self.pending_rows, '$')?;
+            self.pending_rows = 0;
+        }
+
+        let mut cells = row;
+        while let Some(&alive) = cells.first() {
+            let count = cells
+                .iter()
+                .position(|&cell| cell != alive)
+                .unwrap_or(cells.len());
+            cells = &cells[count..];
+            if alive || !cells.is_empty() {
+                self.run(count, if alive { 'o' } else { 'b' })?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> fmt::Result {
+        self.run(1, '!')?;
+        self.out.write_char('\n')
+    }
+}
+
+/// Parses the `x = .., y = .., rule = ..` header line of an RLE pattern.
+fn parse_rle_header(line: &[u8]) -> Option<Header> {
+    let mut header = Header {
+        width: 0,
+        height: 0,
+        rule: None,
+    };
+    for field in line.split(|&byte| byte == b',') {
+        let mut parts = field.splitn(2, |&byte| byte == b'=');
+        let key = parts.next()?.trim_ascii();
+        let value = core::str::from_utf8(parts.next()?).ok()?.trim();
+        match key {
+            b"x" => header.width = value.parse().ok()?,
+            b"y" => header.height = value.parse().ok()?,
+            b"rule" => header.rule = value.parse().ok(),
+            _ => {}
+        }
+    }
+    Some(header)
+}
+
+/// Reads a run length encoded pattern, setting its live cells on `canvas`.
+pub fn read_rle<C: Canvas + ?Sized>(
+    input: impl IntoIterator<Item = u8>,
+    canvas: &mut C,
+) -> Result<Header, PatternError> {
+    let mut input = input.into_iter().enumerate().peekable();
+
+    // Skip comments up to the header line, which is short enough to buffer
+    let mut line = [0; 64];
+    let header = loop {
+        let mut length = 0;
+        for (_, byte) in input.by_ref().take_while(|&(_, byte)| byte != b'\n') {
+            if let Some(slot) = line.get_mut(length) {
+                *slot = byte;
+            }
+            length += 1;
+        }
+        match line[..length.min(line.len())].trim_ascii() {
+            [] | [b'#', ..] if input.peek().is_some() => continue,
+            header @ [b'x', ..] if length <= line.len() => {
+                break parse_rle_header(header).ok_or(PatternError::MissingHeader)?;
+            }
+            _ => return Err(PatternError::MissingHeader),
+        }
+    };
+
+    let (mut row, mut column): (usize, usize) = (0, 0);
+    let mut count: usize = 0;
+    for (offset, byte) in input {
+        match byte {
+            b'0'..=b'9' => {
+                count = count
+                    .checked_mul(10)
+                    .and_then(|count| count.checked_add((byte - b'0') as usize))
+                    .ok_or(PatternError::Overflow(offset))?;
+                continue;
+            }
+            b'b' | b'.' => {
+                column = column
+                    .checked_add(count.max(1))
+                    .ok_or(PatternError::Overflow(offset))?;
+            }
+            b'o' => {
+                for _ in 0..count.max(1) {
+                    set(canvas, row, column)?;
+                    column += 1;
+                }
+            }
+            b'$' => {
+                row = row
+                    .checked_add(count.max(1))
+                    .ok_or(PatternError::Overflow(offset))?;
+                column = 0;
+            }
+            b'!' => break,
+            _ if byte.is_ascii_whitespace() => {}
+            _ => return Err(PatternError::UnexpectedCharacter(offset)),
+        }
+        count = 0;
+    }
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::string::String;
+
+    use super::*;
+
+    const GLIDER: [[bool; 5]; 4] = [
+        [false, true, false, false, false],
+        [false, false, true, false, false],
+        [true, true, true, false, false],
+        [false, false, false, false, false],
+    ];
+
+    #[test]
+    fn test_plaintext_round_trip() {
+        let mut text = String::new();
+        write_plaintext(&mut text, Some("Glider"), GLIDER).unwrap();
+        assert_eq!(text, "!Name: Glider\n.O\n..O\nOOO\n\n");
+
+        let mut grid = [[false; 5]; 4];
+        let header = read_plaintext(text.bytes(), &mut grid[..]).unwrap();
+        assert_eq!((header.width, header.height), (3, 4));
+        assert_eq!(grid, GLIDER);
+
+        let mut row = [false; 2];
+        assert_eq!(
+            read_plaintext(*b"..O", &mut row[..]),
+            Err(PatternError::OutOfBounds { row: 0, column: 2 })
+        );
+    }
+
+    #[test]
+    fn test_rle_round_trip() {
+        let mut text = String::new();
+        let mut writer = RleWriter::new(&mut text, 5, 4, Some(LifeRule::CONWAY)).unwrap();
+        for row in &GLIDER {
+            writer.row(row).unwrap();
+        }
+        writer.finish().unwrap();
+        assert_eq!(text, "x = 5, y = 4, rule = B3/S23\nbo$2bo$3o!\n");
+
+        let mut grid = [[false; 5]; 4];
+        let header = read_rle(text.bytes(), &mut grid[..]).unwrap();
+        assert_eq!(
+            header,
+            Header {
+                width: 5,
+                height: 4,
+                rule: Some(LifeRule::CONWAY)
+            }
+        );
+        assert_eq!(grid, GLIDER);
+    }
+
+    #[test]
+    fn test_rle_read_row() {
+        let text = "#C A comment\nx = 12, y = 1, rule = W30\n3b2o\n4bo!";
+        let mut row = [false; 12];
+        let header = read_rle(text.bytes(), &mut row[..]).unwrap();
+
+        assert_eq!((header.width, header.rule), (12, None));
+        assert_eq!(
+            row.map(|cell| if cell { 'o' } else { 'b' }),
+            ['b', 'b', 'b', 'o', 'o', 'b', 'b', 'b', 'b', 'o', 'b', 'b']
+        );
+        assert_eq!(
+            read_rle(*b"3b2o!", &mut row[..]),
+            Err(PatternError::MissingHeader)
+        );
+        assert_eq!(
+            read_rle(*b"x = 1, y = 1\nz!", &mut row[..]),
+            Err(PatternError::UnexpectedCharacter(13))
+        );
+    }
+
+    #[test]
+    fn test_rle_overflow() {
+        let mut row = [false; 12];
+        assert_eq!(
+            read_rle(*b"x = 1, y = 1\n99999999999999999999b!", &mut row[..]),
+            Err(PatternError::Overflow(32))
+        );
+        assert_eq!(
+            read_rle(*b"x = 1, y = 1\n18446744073709551615b2b!", &mut row[..]),
+            Err(PatternError::Overflow(35))
+        );
+        assert_eq!(
+            read_rle(*b"x = 1, y = 1\n18446744073709551615$$o!", &mut row[..]),
+            Err(PatternError::Overflow(34))
+        );
+    }
+
+    #[test]
+    fn test_pattern_error_display() {
+        let mut message = String::new();
+        write!(
+            message,
+            "{}",
+            PatternError::OutOfBounds { row: 2, column: 5 }
+        )
+        .unwrap();
+        assert_eq!(message, "cell at row 2 column 5 is out of bounds");
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{ElementaryCellularAutomaton, Rule, Wrap};
+
+// Paths through the de Bruijn graph spell out predecessor rows. Each node is a
+// pair of adjacent cells `(p[i - 1], p[i])` packed as `p[i - 1] << 1 | p[i]`,
+// and the edge to `(p[i], p[i + 1])` is labelled with the rule output for the
+// three cells. A row's predecessors are the paths whose labels spell the row
+// and whose first and last nodes agree with the boundary.
+
+fn node(left: bool, right: bool) -> u8 {
+    (left as u8) << 1 | right as u8
+}
+
+fn left(node: u8) -> bool {
+    node & 0b10 != 0
+}
+
+fn right(node: u8) -> bool {
+    node & 0b01 != 0
+}
+
+/// Whether a path from `start` to `end` is consistent with the boundary.
+fn boundary_allows(wrap: Wrap, start: u8, end: u8) -> bool {
+    wrap.edges(right(start), left(end)) == (left(start), right(end))
+}
+
+/// Nodes reachable from any node in `nodes` along edges labelled `cell`.
+fn successors(rule: Rule, nodes: u8, cell: bool) -> u8 {
+    (0..4)
+        .filter(|from| nodes & 1 << from != 0)
+        .flat_map(|from| [false, true].map(|next| (from, next)))
+        .filter(|&(from, next)| rule.apply(left(from), right(from), next) == cell)
+        .fold(0, |reachable, (from, next)| {
+            reachable | 1 << node(right(from), next)
+        })
+}
+
+/// Iterator over every predecessor of a row, created by
+/// `ElementaryCellularAutomaton::preimages`.
+pub struct Preimages<const W: usize> {
+    rule: Rule,
+    wrap: Wrap,
+    state: [bool; W],
+    start: u8,
+    /// Nodes at position `i + 1` which lie on a complete path from `start`.
+    alive: [u8; W],
+    /// Node chosen at position `i + 1` of the current path.
+    path: [u8; W],
+    active: bool,
+}
+
+impl<const W: usize> Preimages<W> {
+    fn new(rule: Rule, wrap: Wrap, state: [bool; W]) -> Self {
+        Self {
+            rule,
+            wrap,
+            state,
+            start: 0,
+            alive: [0; W],
+            path: [0; W],
+            active: false,
+        }
+    }
+
+    /// Prunes the graph down to the nodes on complete paths from `start`,
+    /// returning false if there are none.
+    fn prepare(&mut self) -> bool {
+        let mut reachable = 1 << self.start;
+        for (alive, &cell) in self.alive.iter_mut().zip(&self.state) {
+            reachable = successors(self.rule, reachable, cell);
+            *alive = reachable;
+        }
+
+        let ends = (0..4)
+            .filter(|&end| boundary_allows(self.wrap, self.start, end))
+            .fold(0, |ends, end| ends | 1 << end);
+        self.alive[W - 1] &= ends;
+
+        for i in (0..W - 1).rev() {
+            let next = self.alive[i + 1];
+            let cell = self.state[i + 1];
+            self.alive[i] &= (0..4)
+                .filter(|&from| successors(self.rule, 1 << from, cell) & next != 0)
+                .fold(0, |nodes, from| nodes | 1 << from);
+        }
+
+        self.alive[W - 1] != 0
+    }
+
+    fn previous_node(&self, i: usize) -> u8 {
+        if i == 0 {
+            self.start
+        } else {
+            self.path[i - 1]
+        }
+    }
+
+    fn choice(&self, i: usize, next: bool) -> Option<u8> {
+        let from = self.previous_node(i);
+        let to = node(right(from), next);
+        let valid = self.rule.apply(left(from), right(from), next) == self.state[i]
+            && self.alive[i] & 1 << to != 0;
+        valid.then_some(to)
+    }
+
+    /// Completes the path from position `from` taking the lowest choice at
+    /// each step, which always succeeds once the graph has been pruned.
+    fn fill(&mut self, from: usize) {
+        for i in from..W {
+            self.path[i] = self.choice(i, false).or(self.choice(i, true)).unwrap();
+        }
+    }
+
+    /// Moves to the next path from the current start node.
+    fn advance(&mut self) -> bool {
+        for i in (0..W).rev() {
+            if !right(self.path[i]) {
+                if let Some(to) = self.choice(i, true) {
+                    self.path[i] = to;
+                    self.fill(i + 1);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn current(&self) -> [bool; W] {
+        core::array::from_fn(|i| right(self.previous_node(i)))
+    }
+}
+
+impl<const W: usize> Iterator for Preimages<W> {
+    type Item = [bool; W];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // An empty row is its own only predecessor
+        if W == 0 {
+            let first = self.start == 0;
+            self.start = 4;
+            return first.then_some([false; W]);
+        }
+        if self.active && self.advance() {
+            return Some(self.current());
+        }
+        if self.active {
+            self.start += 1;
+            self.active = false;
+        }
+        while self.start < 4 {
+            if self.prepare() {
+                self.active = true;
+                self.fill(0);
+                return Some(self.current());
+            }
+            self.start += 1;
+        }
+        None
+    }
+}
+
+impl ElementaryCellularAutomaton {
+    /// Enumerates every row whose next generation is `state`.
+    pub fn preimages<const W: usize>(&self, state: &[bool; W]) -> Preimages<W> {
+        Preimages::new(self.rule, self.wrap, *state)
+    }
+
+    /// Counts the rows whose next generation is `state`, saturating at
+    /// `u64::MAX`.
+    pub fn preimage_count(&self, state: &[bool]) -> u64 {
+        if state.is_empty() {
+            return 1;
+        }
+
+        let mut total: u64 = 0;
+        for start in 0..4 {
+            let mut counts = [0u64; 4];
+            counts[start as usize] = 1;
+            for &cell in state {
+                let mut next_counts = [0u64; 4];
+                for (from, &count) in counts.iter().enumerate() {
+                    let reachable = successors(self.rule, 1 << from, cell);
+                    for (to, next_count) in next_counts.iter_mut().enumerate() {
+                        if reachable & 1 << to != 0 {
+                            *next_count = next_count.saturating_add(count);
+                        }
+                    }
+                }
+                counts = next_counts;
+            }
+            for (end, &count) in counts.iter().enumerate() {
+                if boundary_allows(self.wrap, start, end as u8) {
+                    total = total.saturating_add(count);
+                }
+            }
+        }
+        total
+    }
+
+    /// Whether `state` has no predecessor.
+    pub fn is_garden_of_eden(&self, state: &[bool]) -> bool {
+        self.preimage_count(state) == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CellularAutomaton;
+
+    fn encode(state: &[bool; 8]) -> usize {
+        state
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (i, &cell)| acc | (cell as usize) << i)
+    }
+
+    #[test]
+    fn test_preimages_match_brute_force() {
+        for wrap in [
+            Wrap::Wrap,
+            Wrap::Zero,
+            Wrap::One,
+            Wrap::Reflect,
+            Wrap::random(3),
+            Wrap::input(false, true),
+        ] {
+            for rule in [0, 18, 30, 54, 90, 110, 184, 255] {
+                let automaton = ElementaryCellularAutomaton::new(wrap, Rule::new(rule));
+
+                for target in [0, 1, 0b0110_1001, 0b1111_1111, 0b0001_1100] {
+                    let state: [bool; 8] = core::array::from_fn(|i| (target >> i) & 1 == 1);
+
+                    let mut expected = [false; 256];
+                    for (predecessor, expected) in expected.iter_mut().enumerate() {
+                        let predecessor: [bool; 8] =
+                            core::array::from_fn(|i| (predecessor >> i) & 1 == 1);
+                        let mut next_state = [false; 8];
+                        automaton.next(&predecessor, &mut next_state);
+                        *expected = next_state == state;
+                    }
+
+                    let mut found = [false; 256];
+                    for preimage in automaton.preimages(&state) {
+                        assert!(!found[encode(&preimage)]);
+                        found[encode(&preimage)] = true;
+                    }
+
+                    assert_eq!(found, expected);
+                    let count = found.iter().filter(|&&found| found).count();
+                    assert_eq!(automaton.preimage_count(&state), count as u64);
+                    assert_eq!(automaton.is_garden_of_eden(&state), count == 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_preimages_empty_row() {
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Zero, Rule::new(30));
+        let mut preimages = automaton.preimages(&[]);
+        assert_eq!(preimages.next(), Some([]));
+        assert_eq!(preimages.next(), None);
+        assert_eq!(automaton.preimage_count(&[]), 1);
+        assert!(!automaton.is_garden_of_eden(&[]));
+    }
+
+    #[test]
+    fn test_preimages_long_row() {
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(30));
+        let mut state = [false; 100];
+        state[50] = true;
+        let mut next_state = [false; 100];
+        automaton.next(&state, &mut next_state);
+
+        assert!(automaton
+            .preimages(&next_state)
+            .any(|preimage| preimage == state));
+        assert_eq!(
+            automaton.preimages(&next_state).count() as u64,
+            automaton.preimage_count(&next_state)
+        );
+    }
+}
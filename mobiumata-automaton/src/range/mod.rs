@@ -0,0 +1,204 @@
+use defmt::Format;
+use serde::{Deserialize, Serialize};
+
+use crate::{CellularAutomaton, ElementaryCellularAutomaton, ParseRuleError, Rule, Wrap};
+
+/// A one-dimensional binary rule over a neighbourhood of `2 * radius + 1`
+/// cells, numbered using Wolfram's scheme.
+#[derive(Clone, Copy, Debug, PartialEq, Format, Serialize, Deserialize)]
+#[serde(try_from = "RawRangeRule", into = "RawRangeRule")]
+pub struct RangeRule {
+    radius: u8,
+    number: u128,
+}
+
+/// Unchecked form of [`RangeRule`], validated on deserialisation. The number
+/// is split in two as serde-json-core has no `u128` support.
+#[derive(Serialize, Deserialize)]
+struct RawRangeRule {
+    radius: u8,
+    low: u64,
+    high: u64,
+}
+
+impl TryFrom<RawRangeRule> for RangeRule {
+    type Error = ParseRuleError;
+
+    /// Rejects radii beyond `MAX_RADIUS` and numbers with bits set beyond the
+    /// neighbourhoods of the radius.
+    fn try_from(raw: RawRangeRule) -> Result<Self, Self::Error> {
+        let number = (raw.high as u128) << 64 | raw.low as u128;
+        if Self::is_valid(raw.radius, number) {
+            Ok(Self {
+                radius: raw.radius,
+                number,
+            })
+        } else {
+            Err(ParseRuleError::OutOfRange)
+        }
+    }
+}
+
+impl From<RangeRule> for RawRangeRule {
+    fn from(rule: RangeRule) -> Self {
+        Self {
+            radius: rule.radius,
+            low: rule.number as u64,
+            high: (rule.number >> 64) as u64,
+        }
+    }
+}
+
+impl RangeRule {
+    pub const MAX_RADIUS: u8 = 3;
+
+    pub fn new(radius: u8, number: u128) -> Self {
+        assert!(Self::is_valid(radius, number));
+        Self { radius, number }
+    }
+
+    fn is_valid(radius: u8, number: u128) -> bool {
+        if !(1..=Self::MAX_RADIUS).contains(&radius) {
+            return false;
+        }
+        let neighbourhoods = 1u32 << (2 * radius + 1);
+        neighbourhoods == u128::BITS || number >> neighbourhoods == 0
+    }
+
+    pub fn radius_2(number: u32) -> Self {
+        Self::new(2, number as u128)
+    }
+
+    pub fn radius_3(number: u128) -> Self {
+        Self::new(3, number)
+    }
+
+    pub fn radius(&self) -> u8 {
+        self.radius
+    }
+
+    pub fn inner(&self) -> u128 {
+        self.number
+    }
+
+    /// Returns the output for a neighbourhood whose leftmost cell is the most
+    /// significant bit of `index`.
+    pub fn apply(&self, index: usize) -> bool {
+        (self.number >> index) & 1 == 1
+    }
+}
+
+impl From<Rule> for RangeRule {
+    fn from(rule: Rule) -> Self {
+        Self::new(1, rule.inner() as u128)
+    }
+}
+
+pub struct RangeCellularAutomaton {
+    pub wrap: Wrap,
+    pub rule: RangeRule,
+}
+
+impl RangeCellularAutomaton {
+    pub fn new(wrapping: Wrap, rule: RangeRule) -> Self {
+        Self {
+            wrap: wrapping,
+            rule,
+        }
+    }
+}
+
+impl From<ElementaryCellularAutomaton> for RangeCellularAutomaton {
+    fn from(automaton: ElementaryCellularAutomaton) -> Self {
+        Self::new(automaton.wrap, automaton.rule.into())
+    }
+}
+
+impl CellularAutomaton for RangeCellularAutomaton {
+    type Cell = bool;
+
+    fn next(&self, state: &[bool], next_state: &mut [bool]) {
+        assert_eq!(state.len(), next_state.len());
+
+        let radius = self.rule.radius as isize;
+
+        for (i, cell) in next_state.iter_mut().enumerate() {
+            let index = (-radius..=radius).fold(0, |index, offset| {
+                index << 1 | self.wrap.cell(state, i as isize + offset) as usize
+            });
+            *cell = self.rule.apply(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_cellular_automaton_radius_1() {
+        let state = [false, true, true, false, true, false, false, false, true];
+
+        for wrap in [Wrap::Wrap, Wrap::Zero, Wrap::One] {
+            for rule in [30, 90, 110, 184] {
+                let elementary = ElementaryCellularAutomaton::new(wrap, Rule::new(rule));
+                let range = RangeCellularAutomaton::from(ElementaryCellularAutomaton::new(
+                    wrap,
+                    Rule::new(rule),
+                ));
+
+                let mut expected = [false; 9];
+                elementary.next(&state, &mut expected);
+                let mut next_state = [false; 9];
+                range.next(&state, &mut next_state);
+
+                assert_eq!(next_state, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_range_cellular_automaton_shift() {
+        // Copies the leftmost cell of the neighbourhood, shifting the row right
+        let automaton = RangeCellularAutomaton::new(Wrap::Wrap, RangeRule::radius_2(0xffff_0000));
+        let mut state = [[false; 8]; 4];
+        state[0] = [true, true, false, false, false, false, false, true];
+
+        automaton.next_row(&mut state, 1);
+        assert_eq!(
+            state[1],
+            [false, true, true, true, false, false, false, false]
+        );
+
+        let automaton = RangeCellularAutomaton::new(Wrap::Zero, RangeRule::radius_3(!0 << 64));
+        automaton.next_row(&mut state, 2);
+        assert_eq!(
+            state[2],
+            [false, false, false, false, true, true, true, false]
+        );
+
+        assert_eq!(automaton.period::<8, 16>(&state[0]), Some(1));
+        let automaton = RangeCellularAutomaton::new(Wrap::Wrap, RangeRule::radius_3(!0 << 64));
+        assert_eq!(automaton.period::<8, 16>(&state[0]), Some(8));
+    }
+
+    #[test]
+    fn test_range_rule_deserialise() {
+        let rule = RangeRule::radius_3(!0 << 64 | 0x6e);
+        let json = serde_json::to_string(&rule).unwrap();
+        assert_eq!(
+            json,
+            r#"{"radius":3,"low":110,"high":18446744073709551615}"#
+        );
+        assert_eq!(serde_json::from_str::<RangeRule>(&json).unwrap(), rule);
+
+        for json in [
+            r#"{"radius":0,"low":0,"high":0}"#,
+            r#"{"radius":4,"low":0,"high":0}"#,
+            r#"{"radius":1,"low":256,"high":0}"#,
+            r#"{"radius":2,"low":0,"high":1}"#,
+        ] {
+            assert!(serde_json::from_str::<RangeRule>(json).is_err());
+        }
+    }
+}
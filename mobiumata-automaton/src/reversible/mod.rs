@@ -0,0 +1,96 @@
+use crate::{CellularAutomaton, ElementaryCellularAutomaton, Rule, Wrap};
+
+/// Second-order (Fredkin) variant of an elementary automaton where each
+/// generation is the rule output XOR the generation before the previous one,
+/// which makes the evolution exactly reversible.
+pub struct ReversibleCellularAutomaton {
+    pub wrap: Wrap,
+    pub rule: Rule,
+}
+
+impl ReversibleCellularAutomaton {
+    pub fn new(wrapping: Wrap, rule: Rule) -> Self {
+        Self {
+            wrap: wrapping,
+            rule,
+        }
+    }
+
+    pub fn next(&self, previous_state: &[bool], state: &[bool], next_state: &mut [bool]) {
+        assert_eq!(previous_state.len(), state.len());
+
+        ElementaryCellularAutomaton::new(self.wrap, self.rule).next(state, next_state);
+        for (cell, previous) in next_state.iter_mut().zip(previous_state) {
+            *cell ^= previous;
+        }
+    }
+
+    /// Recovers the generation before `state`, given the generation after it.
+    pub fn previous(&self, next_state: &[bool], state: &[bool], previous_state: &mut [bool]) {
+        self.next(next_state, state, previous_state);
+    }
+
+    /// Computes the row at `index` from the two rows before it in the ring.
+    pub fn next_row<const W: usize, const H: usize>(
+        &self,
+        state: &mut [[bool; W]; H],
+        index: usize,
+    ) {
+        assert!(H > 2);
+        let current = state[(index + H - 1) % H];
+        let previous = state[(index + H - 2) % H];
+        self.next(&previous, &current, &mut state[index]);
+    }
+
+    /// Recomputes the row at `index` from the two rows after it in the ring,
+    /// rewinding the evolution by one generation.
+    pub fn previous_row<const W: usize, const H: usize>(
+        &self,
+        state: &mut [[bool; W]; H],
+        index: usize,
+    ) {
+        assert!(H > 2);
+        let current = state[(index + 1) % H];
+        let next = state[(index + 2) % H];
+        self.previous(&next, &current, &mut state[index]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reversible_cellular_automaton_next() {
+        let automaton = ReversibleCellularAutomaton::new(Wrap::Zero, Rule::new(30));
+        let previous_state = [false, false, false, true, false, false, false];
+        let state = [false, false, false, true, false, false, false];
+        let mut next_state = [false; 7];
+
+        automaton.next(&previous_state, &state, &mut next_state);
+
+        assert_eq!(next_state, [false, false, true, false, true, false, false]);
+    }
+
+    #[test]
+    fn test_reversible_cellular_automaton_rewind() {
+        for wrap in [Wrap::Wrap, Wrap::Zero, Wrap::One] {
+            let automaton = ReversibleCellularAutomaton::new(wrap, Rule::new(30));
+            let initial_state = [
+                [false, true, true, false, false, true, false, false],
+                [true, false, false, false, true, true, false, true],
+            ];
+            let mut state = [[false; 8]; 3];
+            state[..2].copy_from_slice(&initial_state);
+
+            for generation in 2..50 {
+                automaton.next_row(&mut state, generation % 3);
+            }
+            for generation in (0..48).rev() {
+                automaton.previous_row(&mut state, generation % 3);
+            }
+
+            assert_eq!(state[..2], initial_state);
+        }
+    }
+}
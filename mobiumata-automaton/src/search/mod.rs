@@ -0,0 +1,337 @@
+use rand_core::RngCore;
+
+use crate::statistics::block_entropy;
+use crate::stochastic::chance;
+use crate::{
+    CellularAutomaton, ElementaryCellularAutomaton, HybridCellularAutomaton,
+    RangeCellularAutomaton, RangeRule, Rule, Wrap,
+};
+
+/// A rule which can be bred by [`Search`].
+pub trait Genome: Copy {
+    type Automaton: CellularAutomaton<Cell = bool>;
+
+    fn random<R: RngCore>(rng: &mut R) -> Self;
+
+    /// Flips each bit of the rule table with probability `rate`.
+    fn mutate<R: RngCore>(&mut self, rate: f32, rng: &mut R);
+
+    /// Takes each bit of the rule table from either parent at random.
+    fn crossover<R: RngCore>(&self, other: &Self, rng: &mut R) -> Self;
+
+    fn automaton(&self, wrapping: Wrap) -> Self::Automaton;
+}
+
+fn random_bits<R: RngCore>(bits: u32, rng: &mut R) -> u128 {
+    let random = (rng.next_u64() as u128) << 64 | rng.next_u64() as u128;
+    if bits < u128::BITS {
+        random & ((1 << bits) - 1)
+    } else {
+        random
+    }
+}
+
+fn mutate_bits<R: RngCore>(number: u128, bits: u32, rate: f32, rng: &mut R) -> u128 {
+    (0..bits).fold(number, |number, bit| {
+        number ^ ((chance(rng, rate) as u128) << bit)
+    })
+}
+
+fn crossover_bits<R: RngCore>(a: u128, b: u128, bits: u32, rng: &mut R) -> u128 {
+    let mask = random_bits(bits, rng);
+    a & mask | b & !mask
+}
+
+impl Genome for Rule {
+    type Automaton = ElementaryCellularAutomaton;
+
+    fn random<R: RngCore>(rng: &mut R) -> Self {
+        Rule::new(random_bits(8, rng) as u8)
+    }
+
+    fn mutate<R: RngCore>(&mut self, rate: f32, rng: &mut R) {
+        *self = Rule::new(mutate_bits(self.inner() as u128, 8, rate, rng) as u8);
+    }
+
+    fn crossover<R: RngCore>(&self, other: &Self, rng: &mut R) -> Self {
+        let number = crossover_bits(self.inner() as u128, other.inner() as u128, 8, rng);
+        Rule::new(number as u8)
+    }
+
+    fn automaton(&self, wrapping: Wrap) -> Self::Automaton {
+        ElementaryCellularAutomaton::new(wrapping, *self)
+    }
+}
+
+/// Random range rules have radius 2. Mutation and crossover keep the radius,
+/// so both parents must share it.
+impl Genome for RangeRule {
+    type Automaton = RangeCellularAutomaton;
+
+    fn random<R: RngCore>(rng: &mut R) -> Self {
+        RangeRule::radius_2(random_bits(32, rng) as u32)
+    }
+
+    fn mutate<R: RngCore>(&mut self, rate: f32, rng: &mut R) {
+        let bits = 1 << (2 * self.radius() + 1);
+        *self = RangeRule::new(self.radius(), mutate_bits(self.inner(), bits, rate, rng));
+    }
+
+    fn crossover<R: RngCore>(&self, other: &Self, rng: &mut R) -> Self {
+        assert_eq!(self.radius(), other.radius());
+        let bits = 1 << (2 * self.radius() + 1);
+        let number = crossover_bits(self.inner(), other.inner(), bits, rng);
+        RangeRule::new(self.radius(), number)
+    }
+
+    fn automaton(&self, wrapping: Wrap) -> Self::Automaton {
+        RangeCellularAutomaton::new(wrapping, *self)
+    }
+}
+
+/// The rules of a hybrid automaton, one per column.
+impl<const W: usize> Genome for [Rule; W] {
+    type Automaton = HybridCellularAutomaton<W>;
+
+    fn random<R: RngCore>(rng: &mut R) -> Self {
+        core::array::from_fn(|_| Rule::random(rng))
+    }
+
+    fn mutate<R: RngCore>(&mut self, rate: f32, rng: &mut R) {
+        for rule in self.iter_mut() {
+            rule.mutate(rate, rng);
+        }
+    }
+
+    fn crossover<R: RngCore>(&self, other: &Self, rng: &mut R) -> Self {
+        core::array::from_fn(|i| self[i].crossover(&other[i], rng))
+    }
+
+    fn automaton(&self, wrapping: Wrap) -> Self::Automaton {
+        HybridCellularAutomaton::new(wrapping, *self)
+    }
+}
+
+/// Generations before `initial` settles into a cycle, or `max_generations`
+/// if no cycle is found by then.
+pub fn transient<G: Genome, const W: usize>(
+    genome: &G,
+    wrapping: Wrap,
+    initial: &[bool; W],
+    max_generations: usize,
+) -> f32 {
+    let cycle = genome
+        .automaton(wrapping)
+        .cycle::<W>(initial, max_generations);
+    cycle.map_or(max_generations, |cycle| cycle.transient) as f32
+}
+
+/// Block entropy of the first `generations` generations from `initial`, see
+/// [`block_entropy`].
+pub fn entropy<G: Genome, const W: usize>(
+    genome: &G,
+    wrapping: Wrap,
+    initial: [bool; W],
+    generations: usize,
+    size: usize,
+) -> f32 {
+    let automaton = genome.automaton(wrapping);
+    block_entropy(automaton.evolve(initial).take(generations), size)
+}
+
+/// Fraction of `trials` random rows, of uniformly distributed density, which
+/// settle within `generations` generations to all live cells if most cells
+/// started live and to all dead cells otherwise.
+pub fn density_classification<G: Genome, R: RngCore, const W: usize>(
+    genome: &G,
+    wrapping: Wrap,
+    trials: usize,
+    generations: usize,
+    rng: &mut R,
+) -> f32 {
+    let automaton = genome.automaton(wrapping);
+    let correct = (0..trials)
+        .filter(|_| {
+            let mut initial = [false; W];
+            let density = rng.next_u32() as f32 / u32::MAX as f32;
+            crate::seed::random(&mut initial, density, rng);
+            let majority = 2 * initial.iter().filter(|&&cell| cell).count() > W;
+            let last = automaton.evolve(initial).nth(generations).unwrap();
+            last.iter().all(|&cell| cell == majority)
+        })
+        .count();
+    correct as f32 / trials.max(1) as f32
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Candidate<G> {
+    pub genome: G,
+    pub fitness: f32,
+}
+
+/// Genetic search over a population of `N` rules, keeping the fittest
+/// `elite` unchanged each generation and breeding the rest from parents
+/// chosen by tournament.
+pub struct Search<G: Genome, F: FnMut(&G) -> f32, R: RngCore, const N: usize> {
+    population: [Candidate<G>; N],
+    fitness: F,
+    rng: R,
+    generation: usize,
+    pub elite: usize,
+    pub tournament: usize,
+    pub mutation_rate: f32,
+}
+
+impl<G: Genome, F: FnMut(&G) -> f32, R: RngCore, const N: usize> Search<G, F, R, N> {
+    /// Starts from a random population scored with `fitness`.
+    pub fn new(mut fitness: F, mut rng: R) -> Self {
+        assert!(N > 0);
+        let mut population = core::array::from_fn(|_| {
+            let genome = G::random(&mut rng);
+            Candidate {
+                genome,
+                fitness: fitness(&genome),
+            }
+        });
+        sort(&mut population);
+        Self {
+            population,
+            fitness,
+            rng,
+            generation: 0,
+            elite: N.div_ceil(10),
+            tournament: 3,
+            mutation_rate: 0.02,
+        }
+    }
+
+    /// Candidates from fittest to least fit.
+    pub fn population(&self) -> &[Candidate<G>; N] {
+        &self.population
+    }
+
+    pub fn best(&self) -> &Candidate<G> {
+        &self.population[0]
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Picks the fittest of `tournament` random candidates from `parents`.
+    fn select(&mut self, parents: &[Candidate<G>; N]) -> G {
+        let mut best = self.rng.next_u32() as usize % N;
+        for _ in 1..self.tournament {
+            best = best.min(self.rng.next_u32() as usize % N);
+        }
+        parents[best].genome
+    }
+
+    pub fn step(&mut self) {
+        let parents = self.population;
+        for i in self.elite.min(N)..N {
+            let (a, b) = (self.select(&parents), self.select(&parents));
+            let mut genome = a.crossover(&b, &mut self.rng);
+            genome.mutate(self.mutation_rate, &mut self.rng);
+            self.population[i] = Candidate {
+                genome,
+                fitness: (self.fitness)(&genome),
+            };
+        }
+        sort(&mut self.population);
+        self.generation += 1;
+    }
+
+    /// Runs `generations` more generations, returning the best candidate.
+    pub fn run(&mut self, generations: usize) -> &Candidate<G> {
+        for _ in 0..generations {
+            self.step();
+        }
+        self.best()
+    }
+}
+
+/// Sorts from fittest to least fit, placing any NaN fitness last.
+fn sort<G>(population: &mut [Candidate<G>]) {
+    population.sort_unstable_by(|a, b| {
+        b.fitness
+            .partial_cmp(&a.fitness)
+            .unwrap_or_else(|| a.fitness.is_nan().cmp(&b.fitness.is_nan()))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SplitMix64;
+
+    #[test]
+    fn test_genome_operators() {
+        let mut rng = SplitMix64::new(1);
+
+        let mut rule = Rule::new(30);
+        rule.mutate(0.0, &mut rng);
+        assert_eq!(rule, Rule::new(30));
+        rule.mutate(1.0, &mut rng);
+        assert_eq!(rule, Rule::new(!30));
+        assert_eq!(
+            Rule::new(110).crossover(&Rule::new(110), &mut rng),
+            Rule::new(110)
+        );
+
+        let child = Rule::new(0x0f).crossover(&Rule::new(0x0e), &mut rng);
+        assert!(child == Rule::new(0x0f) || child == Rule::new(0x0e));
+
+        let mut rule = RangeRule::random(&mut rng);
+        assert_eq!(rule.radius(), 2);
+        rule.mutate(1.0, &mut rng);
+        let rule =
+            RangeRule::from(Rule::new(90)).crossover(&RangeRule::from(Rule::new(0)), &mut rng);
+        assert_eq!(rule.inner() & !90, 0);
+
+        let rules = <[Rule; 4]>::random(&mut rng);
+        assert_eq!(rules.crossover(&rules, &mut rng), rules);
+    }
+
+    #[test]
+    fn test_fitness_functions() {
+        let mut initial = [false; 16];
+        initial[3] = true;
+        assert_eq!(transient(&Rule::new(204), Wrap::Wrap, &initial, 100), 0.0);
+        assert_eq!(transient(&Rule::new(0), Wrap::Wrap, &initial, 100), 1.0);
+        assert_eq!(transient(&Rule::new(170), Wrap::Zero, &initial, 100), 4.0);
+
+        assert!(entropy(&Rule::new(0), Wrap::Wrap, initial, 32, 3) < 0.2);
+        let rule_30 = entropy(&Rule::new(30), Wrap::Wrap, initial, 32, 3);
+        assert!(rule_30 > 2.0 && rule_30 <= 3.0);
+
+        // Rule 0 answers "dead" and rule 255 "live", so with the same rows
+        // and an odd width exactly one of them is right each time
+        let score = |rule| {
+            let mut rng = SplitMix64::new(7);
+            density_classification::<_, _, 49>(&Rule::new(rule), Wrap::Wrap, 100, 10, &mut rng)
+        };
+        assert_eq!(score(0) + score(255), 1.0);
+        assert!(score(0) > 0.3 && score(0) < 0.7);
+    }
+
+    #[test]
+    fn test_search() {
+        // Breed towards rule 110 by counting matching bits
+        let fitness = |rule: &Rule| (!(rule.inner() ^ 110)).count_ones() as f32;
+        let mut search = Search::<_, _, _, 20>::new(fitness, SplitMix64::new(3));
+        let mut best = search.best().fitness;
+        for _ in 0..30 {
+            search.step();
+            assert!(search.best().fitness >= best);
+            best = search.best().fitness;
+        }
+        assert_eq!(search.generation(), 30);
+        assert_eq!(search.best().genome, Rule::new(110));
+
+        // The same seed replays the same search
+        let mut replay = Search::<_, _, _, 20>::new(fitness, SplitMix64::new(3));
+        assert_eq!(replay.run(30), search.best());
+        assert_eq!(replay.population(), search.population());
+    }
+}
@@ -0,0 +1,140 @@
+use rand_core::{impls, Error, RngCore, SeedableRng};
+
+use crate::stochastic::chance;
+
+/// Small seedable generator, so a random fill can be replayed exactly on both
+/// the display and the wasm build from the same seed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl RngCore for SplitMix64 {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for SplitMix64 {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state)
+    }
+}
+
+/// Sets only the centre cell, or the cell right of centre for even widths.
+pub fn centre(state: &mut [bool]) {
+    let centre = state.len() / 2;
+    for (i, cell) in state.iter_mut().enumerate() {
+        *cell = i == centre;
+    }
+}
+
+/// Sets each cell live with probability `density`.
+pub fn random<R: RngCore>(state: &mut [bool], density: f32, rng: &mut R) {
+    for cell in state.iter_mut() {
+        *cell = chance(rng, density);
+    }
+}
+
+/// Repeats `motif` across the row from the first cell.
+pub fn motif(state: &mut [bool], motif: &[bool]) {
+    assert!(!motif.is_empty());
+    for (cell, &motif_cell) in state.iter_mut().zip(motif.iter().cycle()) {
+        *cell = motif_cell;
+    }
+}
+
+/// Sets cell `i` from bit `i` of `mask`, leaving any cells past the 64th dead.
+pub fn bitmask(state: &mut [bool], mask: u64) {
+    for (i, cell) in state.iter_mut().enumerate() {
+        *cell = i < 64 && (mask >> i) & 1 == 1;
+    }
+}
+
+/// Spells out the bytes of `text`, most significant bit first, leaving any
+/// cells past the end of the text dead.
+pub fn text(state: &mut [bool], text: &str) {
+    let mut bits = text
+        .bytes()
+        .flat_map(|byte| (0..8).rev().map(move |bit| (byte >> bit) & 1 == 1));
+    for cell in state.iter_mut() {
+        *cell = bits.next().unwrap_or(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic_seeds() {
+        let mut state = [true; 7];
+
+        centre(&mut state);
+        assert_eq!(state, [false, false, false, true, false, false, false]);
+
+        motif(&mut state, &[true, false, false]);
+        assert_eq!(state, [true, false, false, true, false, false, true]);
+
+        bitmask(&mut state, 0b0110_0101);
+        assert_eq!(state, [true, false, true, false, false, true, true]);
+
+        let mut state = [true; 20];
+        text(&mut state, "Hi");
+        let bits: [u8; 20] = state.map(|cell| cell as u8);
+        assert_eq!(
+            bits,
+            [0, 1, 0, 0, 1, 0, 0, 0, 0, 1, 1, 0, 1, 0, 0, 1, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_random_seed_is_reproducible() {
+        let mut state = [false; 1000];
+        random(&mut state, 0.25, &mut SplitMix64::new(7));
+        let alive = state.iter().filter(|&&cell| cell).count();
+        assert!((200..300).contains(&alive));
+
+        let mut replay = [false; 1000];
+        random(&mut replay, 0.25, &mut SplitMix64::seed_from_u64(7));
+        assert_eq!(replay, state);
+
+        random(&mut replay, 0.0, &mut SplitMix64::new(7));
+        assert_eq!(replay, [false; 1000]);
+    }
+
+    #[test]
+    fn test_split_mix_64() {
+        // First output for seed 0 from the reference implementation
+        assert_eq!(SplitMix64::new(0).next_u64(), 0xe220_a839_7b1d_cdaf);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::CellularAutomaton;
+
+/// Iterator over successive generations of an automaton, starting with the
+/// initial state.
+pub struct Evolve<'a, A: CellularAutomaton, const W: usize> {
+    automaton: &'a A,
+    state: [A::Cell; W],
+}
+
+impl<'a, A: CellularAutomaton, const W: usize> Evolve<'a, A, W> {
+    pub fn new(automaton: &'a A, initial: [A::Cell; W]) -> Self {
+        Self {
+            automaton,
+            state: initial,
+        }
+    }
+}
+
+impl<A: CellularAutomaton, const W: usize> Iterator for Evolve<'_, A, W> {
+    type Item = [A::Cell; W];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let state = self.state;
+        self.automaton.next(&state, &mut self.state);
+        Some(state)
+    }
+}
+
+/// Space-time diagram stored as a ring of rows, where each new generation
+/// overwrites the oldest row as the display does.
+///
+/// The rows may be a fixed array or any other mutable slice, such as a `Vec`.
+pub struct SpaceTime<'a, C, const W: usize> {
+    rows: &'a mut [[C; W]],
+    head: usize,
+}
+
+impl<'a, C: Copy, const W: usize> SpaceTime<'a, C, W> {
+    /// Wraps `rows`, treating the last row as the most recent generation.
+    pub fn new(rows: &'a mut [[C; W]]) -> Self {
+        Self::with_head(rows, 0)
+    }
+
+    /// Wraps `rows` with the next generation written to row `head`.
+    pub fn with_head(rows: &'a mut [[C; W]], head: usize) -> Self {
+        assert!(rows.len() > 1);
+        assert!(head < rows.len());
+        Self { rows, head }
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Index of the row the next generation is written to.
+    pub fn head(&self) -> usize {
+        self.head
+    }
+
+    /// Rows in storage order, as drawn on the display.
+    pub fn rows(&self) -> &[[C; W]] {
+        self.rows
+    }
+
+    pub fn row(&self, index: usize) -> &[C; W] {
+        &self.rows[index]
+    }
+
+    /// Returns the generation `age` generations before the most recent.
+    pub fn generation(&self, age: usize) -> &[C; W] {
+        let height = self.height();
+        assert!(age < height);
+        &self.rows[(self.head + height - 1 - age) % height]
+    }
+
+    pub fn latest(&self) -> &[C; W] {
+        self.generation(0)
+    }
+
+    /// Iterates from the oldest generation to the most recent.
+    pub fn iter(&self) -> impl Iterator<Item = &[C; W]> + '_ {
+        (0..self.height()).map(|i| &self.rows[(self.head + i) % self.height()])
+    }
+
+    pub fn push(&mut self, row: [C; W]) {
+        self.rows[self.head] = row;
+        self.head = (self.head + 1) % self.height();
+    }
+
+    /// Computes the next generation from the most recent one.
+    pub fn step<A: CellularAutomaton<Cell = C>>(&mut self, automaton: &A) {
+        let latest = *self.latest();
+        automaton.next(&latest, &mut self.rows[self.head]);
+        self.head = (self.head + 1) % self.height();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ElementaryCellularAutomaton, Rule, Wrap};
+
+    #[test]
+    fn test_evolve() {
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Zero, Rule::new(90));
+        let mut generations = automaton.evolve([false, false, false, true, false, false, false]);
+
+        assert_eq!(
+            generations.next(),
+            Some([false, false, false, true, false, false, false])
+        );
+        assert_eq!(
+            generations.next(),
+            Some([false, false, true, false, true, false, false])
+        );
+        assert_eq!(
+            generations.nth(1),
+            Some([true, false, true, false, true, false, true])
+        );
+    }
+
+    #[test]
+    fn test_space_time_matches_next_row() {
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(30));
+        let mut initial = [[false; 8]; 4];
+        initial[3][4] = true;
+
+        let mut expected = initial;
+        let mut rows = initial;
+        let mut diagram = SpaceTime::new(&mut rows);
+        for index in [0, 1, 2, 3, 0, 1] {
+            automaton.next_row(&mut expected, index);
+            diagram.step(&automaton);
+        }
+
+        assert_eq!(diagram.head(), 2);
+        assert_eq!(diagram.rows(), expected);
+        assert_eq!(diagram.latest(), &expected[1]);
+        assert_eq!(diagram.generation(3), &expected[2]);
+    }
+
+    #[test]
+    fn test_space_time_iter() {
+        let mut rows = [[0u8; 1]; 3];
+        let mut diagram = SpaceTime::new(&mut rows);
+        for generation in 1..=4 {
+            diagram.push([generation]);
+        }
+
+        let generations: [u8; 3] = core::array::from_fn(|i| diagram.iter().nth(i).unwrap()[0]);
+        assert_eq!(generations, [2, 3, 4]);
+        assert_eq!(diagram.rows(), [[4], [2], [3]]);
+    }
+}
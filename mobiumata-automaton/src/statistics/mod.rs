@@ -0,0 +1,126 @@
+use crate::math::log2;
+
+/// Largest block size for which block entropy can be measured.
+pub const MAX_BLOCK_SIZE: usize = 8;
+
+/// Fraction of cells which are live.
+pub fn density(row: &[bool]) -> f32 {
+    if row.is_empty() {
+        return 0.0;
+    }
+    row.iter().filter(|&&cell| cell).count() as f32 / row.len() as f32
+}
+
+/// Fraction of cells which changed since the previous generation.
+pub fn activity(previous: &[bool], row: &[bool]) -> f32 {
+    assert_eq!(previous.len(), row.len());
+    if row.is_empty() {
+        return 0.0;
+    }
+    let changed = previous.iter().zip(row).filter(|(a, b)| a != b).count();
+    changed as f32 / row.len() as f32
+}
+
+/// Shannon entropy in bits of the blocks of `size` adjacent cells found in a
+/// row, or across all the rows of a space-time window.
+pub fn block_entropy<R: AsRef<[bool]>>(rows: impl IntoIterator<Item = R>, size: usize) -> f32 {
+    assert!((1..=MAX_BLOCK_SIZE).contains(&size));
+
+    let mut counts = [0u32; 1 << MAX_BLOCK_SIZE];
+    let mut total = 0;
+    for row in rows {
+        for block in row.as_ref().windows(size) {
+            let index = block
+                .iter()
+                .fold(0, |index, &cell| index << 1 | cell as usize);
+            counts[index] += 1;
+            total += 1;
+        }
+    }
+
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f32 / total as f32;
+            -p * log2(p)
+        })
+        .sum()
+}
+
+/// Fills `entropies[k - 1]` with the block entropy for blocks of size `k`.
+pub fn block_entropies<R: AsRef<[bool]>, I: IntoIterator<Item = R> + Clone>(
+    rows: I,
+    entropies: &mut [f32],
+) {
+    for (i, entropy) in entropies.iter_mut().enumerate() {
+        *entropy = block_entropy(rows.clone(), i + 1);
+    }
+}
+
+/// Counts the runs of dead and live cells in a row by length, where index
+/// `n` counts runs of `n + 1` cells and the last index also counts any
+/// longer runs. Runs are left uncounted if their histogram is empty.
+pub fn run_lengths(row: &[bool], dead: &mut [usize], alive: &mut [usize]) {
+    let mut cells = row;
+    while let Some(&cell) = cells.first() {
+        let length = cells
+            .iter()
+            .position(|&other| other != cell)
+            .unwrap_or(cells.len());
+        let histogram = if cell { &mut *alive } else { &mut *dead };
+        if let Some(last) = histogram.len().checked_sub(1) {
+            histogram[(length - 1).min(last)] += 1;
+        }
+        cells = &cells[length..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CellularAutomaton, ElementaryCellularAutomaton, Rule, Wrap};
+
+    #[test]
+    fn test_density_and_activity() {
+        let previous = [true, false, false, true];
+        let row = [true, true, false, false];
+
+        assert_eq!(density(&row), 0.5);
+        assert_eq!(activity(&previous, &row), 0.5);
+        assert_eq!(activity(&row, &row), 0.0);
+    }
+
+    #[test]
+    fn test_block_entropy() {
+        let alternating: [bool; 16] = core::array::from_fn(|i| i % 2 == 0);
+        let mut entropies = [0.0; 3];
+        block_entropies([alternating], &mut entropies);
+        assert!(entropies.iter().all(|entropy| (entropy - 1.0).abs() < 0.01));
+
+        assert_eq!(block_entropy([[false; 8]], 2), 0.0);
+
+        // Rule 30 from a random row is close to the maximum of a bit per cell
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(30));
+        let initial: [bool; 101] = core::array::from_fn(|i| (i * i) % 7 < 3);
+        let window = automaton.evolve(initial).skip(50).take(50);
+        assert!(block_entropy(window, 4) > 3.9);
+    }
+
+    #[test]
+    fn test_run_lengths() {
+        let row = [
+            true, false, false, true, true, true, true, false, true, true,
+        ];
+        let mut dead = [0; 3];
+        let mut alive = [0; 3];
+        run_lengths(&row, &mut dead, &mut alive);
+
+        assert_eq!(dead, [1, 1, 0]);
+        assert_eq!(alive, [1, 1, 1]);
+
+        let mut alive = [0; 2];
+        run_lengths(&row, &mut [], &mut alive);
+        assert_eq!(alive, [1, 2]);
+    }
+}
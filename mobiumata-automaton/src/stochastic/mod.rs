@@ -0,0 +1,190 @@
+use defmt::Format;
+use rand_core::RngCore;
+
+use crate::{Rule, Wrap};
+
+/// Order in which a sequential update visits the cells of a row.
+#[derive(Clone, Copy, Debug, PartialEq, Format)]
+pub enum Sweep {
+    LeftToRight,
+    RightToLeft,
+    /// Updates as many randomly chosen cells as there are in the row,
+    /// possibly visiting some cells more than once.
+    Random,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Format)]
+pub enum UpdateMode {
+    /// Every cell updates, then each output cell flips with the given
+    /// probability.
+    Noise(f32),
+    /// Each cell updates with the given probability (α), otherwise keeping its
+    /// state.
+    Asynchronous(f32),
+    /// Cells update one at a time, seeing any neighbours already updated.
+    Sequential(Sweep),
+}
+
+/// An elementary automaton whose generations are perturbed by a caller
+/// supplied random number generator.
+pub struct StochasticCellularAutomaton {
+    pub wrap: Wrap,
+    pub rule: Rule,
+    pub mode: UpdateMode,
+}
+
+/// Returns true with the given probability.
+pub(crate) fn chance<R: RngCore>(rng: &mut R, probability: f32) -> bool {
+    probability >= 1.0 || rng.next_u32() < (probability * 4_294_967_296.0) as u32
+}
+
+impl StochasticCellularAutomaton {
+    pub fn new(wrapping: Wrap, rule: Rule, mode: UpdateMode) -> Self {
+        Self {
+            wrap: wrapping,
+            rule,
+            mode,
+        }
+    }
+
+    fn update(&self, state: &[bool], i: usize) -> bool {
+        self.rule.apply(
+            self.wrap.left(state, i),
+            state[i],
+            self.wrap.right(state, i),
+        )
+    }
+
+    pub fn next<R: RngCore>(&self, state: &[bool], next_state: &mut [bool], rng: &mut R) {
+        assert_eq!(state.len(), next_state.len());
+
+        match self.mode {
+            UpdateMode::Noise(probability) => {
+                for (i, cell) in next_state.iter_mut().enumerate() {
+                    *cell = self.update(state, i) ^ chance(rng, probability);
+                }
+            }
+            UpdateMode::Asynchronous(alpha) => {
+                for (i, cell) in next_state.iter_mut().enumerate() {
+                    *cell = if chance(rng, alpha) {
+                        self.update(state, i)
+                    } else {
+                        state[i]
+                    };
+                }
+            }
+            UpdateMode::Sequential(sweep) => {
+                next_state.copy_from_slice(state);
+                let len = state.len();
+                for step in 0..len {
+                    let i = match sweep {
+                        Sweep::LeftToRight => step,
+                        Sweep::RightToLeft => len - 1 - step,
+                        Sweep::Random => (rng.next_u64() % len as u64) as usize,
+                    };
+                    next_state[i] = self.update(next_state, i);
+                }
+            }
+        }
+    }
+
+    pub fn next_row<const W: usize, const H: usize, R: RngCore>(
+        &self,
+        state: &mut [[bool; W]; H],
+        index: usize,
+        rng: &mut R,
+    ) {
+        assert!(H > 2);
+        let previous_row = state[(index + H - 1) % H];
+        self.next(&previous_row, &mut state[index], rng);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    use super::*;
+    use crate::{CellularAutomaton, ElementaryCellularAutomaton};
+
+    const STATE: [bool; 10] = [
+        false, true, true, false, true, false, false, false, true, true,
+    ];
+
+    fn deterministic(wrap: Wrap, rule: Rule) -> [bool; 10] {
+        let mut next_state = [false; 10];
+        ElementaryCellularAutomaton::new(wrap, rule).next(&STATE, &mut next_state);
+        next_state
+    }
+
+    #[test]
+    fn test_stochastic_limits() {
+        let mut rng = XorShiftRng::seed_from_u64(30);
+        let rule = Rule::new(30);
+        let expected = deterministic(Wrap::Wrap, rule);
+        let mut next_state = [false; 10];
+
+        let automaton = StochasticCellularAutomaton::new(Wrap::Wrap, rule, UpdateMode::Noise(0.0));
+        automaton.next(&STATE, &mut next_state, &mut rng);
+        assert_eq!(next_state, expected);
+
+        let automaton = StochasticCellularAutomaton::new(Wrap::Wrap, rule, UpdateMode::Noise(1.0));
+        automaton.next(&STATE, &mut next_state, &mut rng);
+        assert_eq!(next_state, expected.map(|cell| !cell));
+
+        let automaton =
+            StochasticCellularAutomaton::new(Wrap::Wrap, rule, UpdateMode::Asynchronous(1.0));
+        automaton.next(&STATE, &mut next_state, &mut rng);
+        assert_eq!(next_state, expected);
+
+        let automaton =
+            StochasticCellularAutomaton::new(Wrap::Wrap, rule, UpdateMode::Asynchronous(0.0));
+        automaton.next(&STATE, &mut next_state, &mut rng);
+        assert_eq!(next_state, STATE);
+    }
+
+    #[test]
+    fn test_stochastic_sequential() {
+        let mut rng = XorShiftRng::seed_from_u64(170);
+        // Rule 170 copies the right neighbour, so a left to right sweep
+        // shifts the row left with the last cell seeing the updated first cell
+        let automaton = StochasticCellularAutomaton::new(
+            Wrap::Wrap,
+            Rule::new(170),
+            UpdateMode::Sequential(Sweep::LeftToRight),
+        );
+        let mut next_state = [false; 10];
+        automaton.next(&STATE, &mut next_state, &mut rng);
+
+        let mut expected = deterministic(Wrap::Wrap, Rule::new(170));
+        expected[9] = STATE[1];
+        assert_eq!(next_state, expected);
+
+        // Sweeping the other way each cell copies its already updated neighbour
+        let automaton = StochasticCellularAutomaton::new(
+            Wrap::Wrap,
+            Rule::new(170),
+            UpdateMode::Sequential(Sweep::RightToLeft),
+        );
+        automaton.next(&STATE, &mut next_state, &mut rng);
+        assert_eq!(next_state, [STATE[0]; 10]);
+    }
+
+    #[test]
+    fn test_stochastic_reproducible() {
+        let automaton =
+            StochasticCellularAutomaton::new(Wrap::Wrap, Rule::new(204), UpdateMode::Noise(0.25));
+        let mut state = [[false; 100]; 4];
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        automaton.next_row(&mut state, 1, &mut rng);
+
+        let flipped = state[1].iter().filter(|&&cell| cell).count();
+        assert!((10..40).contains(&flipped));
+
+        let mut replay = [[false; 100]; 4];
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        automaton.next_row(&mut replay, 1, &mut rng);
+        assert_eq!(replay, state);
+    }
+}
@@ -0,0 +1,72 @@
+use core::fmt::{self, Display, Formatter};
+
+use crate::{SpaceTime, Universe};
+
+/// Renders a row of binary cells as text, using block characters or, with
+/// the alternate flag (`{:#}`), plain ASCII.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cells<'a>(pub &'a [bool]);
+
+impl Display for Cells<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (alive, dead) = if f.alternate() {
+            ('#', '.')
+        } else {
+            ('█', ' ')
+        };
+        for &cell in self.0 {
+            write!(f, "{}", if cell { alive } else { dead })?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes one line per generation, oldest first.
+fn diagram<'a>(f: &mut Formatter<'_>, rows: impl Iterator<Item = &'a [bool]>) -> fmt::Result {
+    for row in rows {
+        if f.alternate() {
+            writeln!(f, "{:#}", Cells(row))?;
+        } else {
+            writeln!(f, "{}", Cells(row))?;
+        }
+    }
+    Ok(())
+}
+
+impl<const W: usize> Display for SpaceTime<'_, bool, W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        diagram(f, self.iter().map(|row| &row[..]))
+    }
+}
+
+impl Display for Universe<bool> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        diagram(f, self.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::format;
+
+    use super::*;
+    use crate::{ElementaryCellularAutomaton, Rule, Wrap};
+
+    #[test]
+    fn test_cells_display() {
+        let row = [true, false, true, true];
+
+        assert_eq!(format!("{}", Cells(&row)), "█ ██");
+        assert_eq!(format!("{:#}", Cells(&row)), "#.##");
+    }
+
+    #[test]
+    fn test_universe_display() {
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Zero, Rule::new(90));
+        let mut universe = Universe::from_row(&[false, false, true, false, false], 3);
+        universe.step(&automaton);
+        universe.step(&automaton);
+
+        assert_eq!(format!("{:#}", universe), "..#..\n.#.#.\n#...#\n");
+    }
+}
@@ -0,0 +1,310 @@
+use defmt::Format;
+use serde::{Deserialize, Serialize};
+
+use crate::{mobius, ParseRuleError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Format, Serialize, Deserialize)]
+pub enum Turn {
+    Left,
+    Right,
+    None,
+    UTurn,
+}
+
+impl Turn {
+    fn from_bits(bits: u32) -> Self {
+        match bits & 0b11 {
+            0 => Turn::Left,
+            1 => Turn::Right,
+            2 => Turn::None,
+            _ => Turn::UTurn,
+        }
+    }
+
+    fn bits(&self) -> u32 {
+        match self {
+            Turn::Left => 0,
+            Turn::Right => 1,
+            Turn::None => 2,
+            Turn::UTurn => 3,
+        }
+    }
+}
+
+/// A generalised Langton's ant rule such as "RL" or "LLRR", giving the turn
+/// taken on each cell colour. The ant then advances the colour of the cell it
+/// leaves, cycling through the colours in order.
+#[derive(Clone, Copy, Debug, PartialEq, Format, Serialize, Deserialize)]
+#[serde(try_from = "RawTurmiteRule")]
+pub struct TurmiteRule {
+    turns: u32,
+    colours: u8,
+}
+
+/// Unchecked form of [`TurmiteRule`], validated on deserialisation.
+#[derive(Deserialize)]
+struct RawTurmiteRule {
+    turns: u32,
+    colours: u8,
+}
+
+impl TryFrom<RawTurmiteRule> for TurmiteRule {
+    type Error = ParseRuleError;
+
+    /// Rejects rules without between 1 and `MAX_COLOURS` colours, or with
+    /// turns set for colours beyond them.
+    fn try_from(raw: RawTurmiteRule) -> Result<Self, Self::Error> {
+        let turns_used = (1u64 << (2 * raw.colours as u32).min(32)) - 1;
+        if (1..=Self::MAX_COLOURS).contains(&raw.colours) && raw.turns as u64 & !turns_used == 0 {
+            Ok(Self {
+                turns: raw.turns,
+                colours: raw.colours,
+            })
+        } else {
+            Err(ParseRuleError::OutOfRange)
+        }
+    }
+}
+
+impl TurmiteRule {
+    pub const MAX_COLOURS: u8 = 16;
+    pub const LANGTONS_ANT: Self = Self {
+        turns: 0b00_01,
+        colours: 2,
+    };
+
+    pub fn new(turns: &[Turn]) -> Self {
+        assert!((1..=Self::MAX_COLOURS as usize).contains(&turns.len()));
+        Self {
+            turns: turns
+                .iter()
+                .rev()
+                .fold(0, |bits, turn| bits << 2 | turn.bits()),
+            colours: turns.len() as u8,
+        }
+    }
+
+    /// Parses a rule string of `L`, `R`, `N` (no turn) and `U` (U-turn).
+    pub fn parse(rule: &str) -> Option<Self> {
+        rule.parse().ok()
+    }
+
+    pub fn colours(&self) -> u8 {
+        self.colours
+    }
+
+    pub fn turn(&self, colour: u8) -> Turn {
+        assert!(colour < self.colours);
+        Turn::from_bits(self.turns >> (2 * colour))
+    }
+}
+
+/// Direction of travel in grid coordinates, East being along the strip
+/// towards increasing rows and North across it towards increasing cells.
+/// Listed anticlockwise, i.e. in order of left turns.
+#[derive(Clone, Copy, Debug, PartialEq, Format)]
+pub enum Heading {
+    East,
+    North,
+    West,
+    South,
+}
+
+impl Heading {
+    const ALL: [Heading; 4] = [Heading::East, Heading::North, Heading::West, Heading::South];
+
+    fn rotate(&self, quarter_turns: usize) -> Self {
+        Self::ALL[(*self as usize + quarter_turns) % 4]
+    }
+
+    fn offset(&self) -> (isize, isize) {
+        match self {
+            Heading::East => (1, 0),
+            Heading::North => (0, 1),
+            Heading::West => (-1, 0),
+            Heading::South => (0, -1),
+        }
+    }
+
+    /// The heading after mirroring the grid across its width.
+    fn mirror(&self) -> Self {
+        match self {
+            Heading::North => Heading::South,
+            Heading::South => Heading::North,
+            heading => *heading,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Format)]
+pub struct Ant {
+    pub row: usize,
+    pub cell: usize,
+    pub heading: Heading,
+    /// Whether the ant has crossed the half-twist an odd number of times, so
+    /// that its left is the grid's right.
+    pub flipped: bool,
+}
+
+impl Ant {
+    pub const fn new(row: usize, cell: usize, heading: Heading) -> Self {
+        Self {
+            row,
+            cell,
+            heading,
+            flipped: false,
+        }
+    }
+
+    fn turn(&mut self, turn: Turn) {
+        let left = if self.flipped { 3 } else { 1 };
+        self.heading = match turn {
+            Turn::Left => self.heading.rotate(left),
+            Turn::Right => self.heading.rotate(4 - left),
+            Turn::None => self.heading,
+            Turn::UTurn => self.heading.rotate(2),
+        };
+    }
+}
+
+/// Turmites walking over a grid of `L` rows of `W` coloured cells glued into
+/// a Möbius strip. Ants reaching the strip's edge turn around.
+pub struct TurmiteAutomaton {
+    pub rule: TurmiteRule,
+}
+
+impl TurmiteAutomaton {
+    pub fn new(rule: TurmiteRule) -> Self {
+        Self { rule }
+    }
+
+    /// Moves each ant in turn by one cell.
+    pub fn step<const W: usize, const L: usize>(&self, state: &mut [[u8; W]; L], ants: &mut [Ant]) {
+        for ant in ants {
+            let colour = &mut state[ant.row][ant.cell];
+            ant.turn(self.rule.turn(*colour));
+            *colour = (*colour + 1) % self.rule.colours;
+
+            let (row_offset, cell_offset) = ant.heading.offset();
+            match mobius::glue::<W, L>(
+                ant.row as isize + row_offset,
+                ant.cell as isize + cell_offset,
+            ) {
+                Some(position) => {
+                    ant.row = position.row;
+                    ant.cell = position.cell;
+                    if position.flipped {
+                        ant.flipped = !ant.flipped;
+                        ant.heading = ant.heading.mirror();
+                    }
+                }
+                None => ant.heading = ant.heading.rotate(2),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turmite_rule() {
+        assert_eq!(TurmiteRule::parse("RL"), Some(TurmiteRule::LANGTONS_ANT));
+        let rule = TurmiteRule::parse("llrU").unwrap();
+        assert_eq!(rule.colours(), 4);
+        assert_eq!(
+            [0, 1, 2, 3].map(|colour| rule.turn(colour)),
+            [Turn::Left, Turn::Left, Turn::Right, Turn::UTurn]
+        );
+        assert_eq!(TurmiteRule::parse(""), None);
+        assert_eq!(TurmiteRule::parse("LRX"), None);
+    }
+
+    #[test]
+    fn test_turmite_rule_deserialise() {
+        let rule: TurmiteRule = serde_json::from_str(r#"{"turns":1,"colours":2}"#).unwrap();
+        assert_eq!(rule, TurmiteRule::LANGTONS_ANT);
+
+        for json in [
+            r#"{"turns":0,"colours":0}"#,
+            r#"{"turns":0,"colours":17}"#,
+            r#"{"turns":16,"colours":2}"#,
+        ] {
+            assert!(serde_json::from_str::<TurmiteRule>(json).is_err());
+        }
+    }
+
+    #[test]
+    fn test_langtons_ant() {
+        let automaton = TurmiteAutomaton::new(TurmiteRule::LANGTONS_ANT);
+        let mut state = [[0; 8]; 16];
+        let mut ants = [Ant::new(8, 4, Heading::East)];
+
+        // Turns right around a square of white cells, then left on the black cell
+        automaton.step(&mut state, &mut ants);
+        assert_eq!(ants[0], Ant::new(8, 3, Heading::South));
+        for _ in 0..3 {
+            automaton.step(&mut state, &mut ants);
+        }
+        assert_eq!(ants[0], Ant::new(8, 4, Heading::East));
+        automaton.step(&mut state, &mut ants);
+        assert_eq!(ants[0], Ant::new(8, 5, Heading::North));
+        assert_eq!(state[8][4], 0);
+        assert_eq!(state[8][3], 1);
+    }
+
+    #[test]
+    fn test_turmite_across_twist() {
+        let automaton = TurmiteAutomaton::new(TurmiteRule::parse("N").unwrap());
+        let mut state = [[0; 8]; 16];
+        let mut ants = [Ant::new(15, 2, Heading::East)];
+
+        automaton.step(&mut state, &mut ants);
+        assert_eq!(
+            ants[0],
+            Ant {
+                row: 0,
+                cell: 5,
+                heading: Heading::East,
+                flipped: true
+            }
+        );
+
+        // Having crossed the twist, a left turn goes right in grid terms
+        let automaton = TurmiteAutomaton::new(TurmiteRule::parse("L").unwrap());
+        automaton.step(&mut state, &mut ants);
+        assert_eq!((ants[0].row, ants[0].cell), (0, 4));
+        assert_eq!(ants[0].heading, Heading::South);
+
+        // Crossing back the other way restores the ant's handedness
+        let mut ants = [Ant {
+            row: 0,
+            cell: 1,
+            heading: Heading::West,
+            flipped: true,
+        }];
+        let automaton = TurmiteAutomaton::new(TurmiteRule::parse("N").unwrap());
+        automaton.step(&mut state, &mut ants);
+        assert_eq!(ants[0], Ant::new(15, 6, Heading::West));
+    }
+
+    #[test]
+    fn test_turmite_edge() {
+        let automaton = TurmiteAutomaton::new(TurmiteRule::parse("N").unwrap());
+        let mut state = [[0; 8]; 16];
+        let mut ants = [
+            Ant::new(3, 0, Heading::South),
+            Ant::new(3, 7, Heading::North),
+        ];
+
+        automaton.step(&mut state, &mut ants);
+        assert_eq!(
+            ants,
+            [
+                Ant::new(3, 0, Heading::North),
+                Ant::new(3, 7, Heading::South)
+            ]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{CellularAutomaton, Cycle};
+
+/// Heap-backed space-time diagram whose width and height are chosen at
+/// runtime, stored as a ring of rows like [`SpaceTime`](crate::SpaceTime).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Universe<C> {
+    width: usize,
+    cells: Vec<C>,
+    head: usize,
+}
+
+impl<C: Copy + Default> Universe<C> {
+    pub fn new(width: usize, height: usize) -> Self {
+        assert!(width > 0);
+        assert!(height > 1);
+        Self {
+            width,
+            cells: vec![C::default(); width * height],
+            head: 0,
+        }
+    }
+
+    /// Creates a universe whose most recent generation is `initial`.
+    pub fn from_row(initial: &[C], height: usize) -> Self {
+        let mut universe = Self::new(initial.len(), height);
+        universe.row_mut(height - 1).copy_from_slice(initial);
+        universe
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len() / self.width
+    }
+
+    /// Index of the row the next generation is written to.
+    pub fn head(&self) -> usize {
+        self.head
+    }
+
+    pub fn row(&self, index: usize) -> &[C] {
+        &self.cells[index * self.width..(index + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, index: usize) -> &mut [C] {
+        &mut self.cells[index * self.width..(index + 1) * self.width]
+    }
+
+    /// Returns the generation `age` generations before the most recent.
+    pub fn generation(&self, age: usize) -> &[C] {
+        let height = self.height();
+        assert!(age < height);
+        self.row((self.head + height - 1 - age) % height)
+    }
+
+    pub fn latest(&self) -> &[C] {
+        self.generation(0)
+    }
+
+    /// Iterates from the oldest generation to the most recent.
+    pub fn iter(&self) -> impl Iterator<Item = &[C]> + '_ {
+        (0..self.height()).map(|i| self.row((self.head + i) % self.height()))
+    }
+
+    pub fn push(&mut self, row: &[C]) {
+        let head = self.head;
+        self.row_mut(head).copy_from_slice(row);
+        self.head = (head + 1) % self.height();
+    }
+
+    /// Computes the next generation from the most recent one.
+    pub fn step<A: CellularAutomaton<Cell = C>>(&mut self, automaton: &A) {
+        let (width, height, head) = (self.width, self.height(), self.head);
+        if head == 0 {
+            let (next, rest) = self.cells.split_at_mut(width);
+            automaton.next(&rest[(height - 2) * width..], next);
+        } else {
+            let (before, after) = self.cells.split_at_mut(head * width);
+            automaton.next(&before[(head - 1) * width..], &mut after[..width]);
+        }
+        self.head = (head + 1) % height;
+    }
+}
+
+/// Finds the cycle reached from `initial_state`, for widths only known at
+/// runtime, giving up after `max_generations` generations.
+pub fn cycle<A: CellularAutomaton>(
+    automaton: &A,
+    initial_state: &[A::Cell],
+    max_generations: usize,
+) -> Option<Cycle> {
+    let step = |state: &Vec<A::Cell>| {
+        let mut next_state = vec![A::Cell::default(); state.len()];
+        automaton.next(state, &mut next_state);
+        next_state
+    };
+    Cycle::find(initial_state.to_vec(), step, max_generations)
+}
+
+/// Returns the period of the cycle if it is reached and repeated within the
+/// first `max` generations.
+pub fn period<A: CellularAutomaton>(
+    automaton: &A,
+    initial_state: &[A::Cell],
+    max: usize,
+) -> Option<usize> {
+    Cycle::period_within(max, |generations| {
+        cycle(automaton, initial_state, generations)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ElementaryCellularAutomaton, Rule, SpaceTime, Wrap};
+
+    #[test]
+    fn test_universe_matches_space_time() {
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(110));
+        let mut initial = [false; 13];
+        initial[6] = true;
+
+        let mut rows = [[false; 13]; 5];
+        rows[4] = initial;
+        let mut diagram = SpaceTime::new(&mut rows);
+        let mut universe = Universe::from_row(&initial, 5);
+        for _ in 0..7 {
+            diagram.step(&automaton);
+            universe.step(&automaton);
+        }
+
+        assert_eq!(universe.head(), diagram.head());
+        for (index, row) in diagram.rows().iter().enumerate() {
+            assert_eq!(universe.row(index), row);
+        }
+        assert!(universe.iter().eq(diagram.iter().map(|row| &row[..])));
+    }
+
+    #[test]
+    fn test_universe_cycle() {
+        let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(30));
+        let mut initial = [false; 12];
+        initial[0] = true;
+
+        assert_eq!(
+            cycle(&automaton, &initial, 1000),
+            automaton.cycle::<12>(&initial, 1000)
+        );
+        assert_eq!(
+            period(&automaton, &initial, 100),
+            automaton.period::<12, 100>(&initial)
+        );
+    }
+}
@@ -118,8 +118,13 @@ pub async fn udp_listen(
     loop {
         let mut buffer = [0; 1024];
         let (len, _) = socket.recv_from(&mut buffer).await.expect("recv failed");
-        let (state, _) = serde_json_core::from_slice(&buffer[..len]).expect("deserialize failed");
-        signal.signal(state);
+        match serde_json_core::from_slice::<State>(&buffer[..len]) {
+            Ok((state, _)) => match state.validate() {
+                Ok(()) => signal.signal(state),
+                Err(error) => defmt::warn!("Ignoring state with invalid {}", error),
+            },
+            Err(_) => defmt::warn!("Ignoring invalid state packet"),
+        }
     }
 }
 
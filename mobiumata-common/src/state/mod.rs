@@ -1,7 +1,8 @@
 use defmt::Format;
 use serde::{Deserialize, Serialize};
 
-use crate::automaton::{Rule, Wrap};
+use crate::automaton::{DomainFilter, LifeRule, RangeRule, Rule, TurmiteRule, Wrap};
+use crate::display::WIDTH;
 
 #[derive(Clone, Copy, Debug, PartialEq, Format, Serialize, Deserialize)]
 pub struct Step(bool);
@@ -16,11 +17,44 @@ impl Step {
     }
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Format, Serialize, Deserialize)]
+pub enum Program {
+    #[default]
+    Elementary,
+    Life(LifeRule),
+    Turmite(TurmiteRule),
+    /// A binary rule over a wider neighbourhood, run in place of `rule` with
+    /// the same `wrap`.
+    Range(RangeRule),
+}
+
+/// A field of a [`State`] which deserialised but holds values the display
+/// cannot run.
+#[derive(Clone, Copy, Debug, PartialEq, Format)]
+pub enum InvalidState {
+    Wrap,
+    Filter,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Format, Serialize, Deserialize)]
 pub struct State {
 pub rule: Rule,
     pub wrap: Wrap,
     pub step: Step,
+    /// Falls back to the elementary automaton for senders which predate it.
+    #[serde(default)]
+    pub program: Program,
+    /// Highlights cells that differ from a shadow universe with one cell
+    /// flipped.
+    #[serde(default)]
+    pub damage: bool,
+    /// Colours particles differently from the background domain.
+    #[serde(default)]
+    pub filter: Option<DomainFilter>,
+    /// Refills the universe from this seed whenever it changes, replaying
+    /// the random fill of a run whose seed the display logged.
+    #[serde(default)]
+    pub seed: Option<u64>,
 }
 
 impl Default for State {
@@ -29,6 +63,26 @@ impl Default for State {
             rule: Rule::new(30),
             wrap: Wrap::Wrap,
             step: Step::new(false),
+            program: Program::Elementary,
+            damage: false,
+            filter: None,
+            seed: None,
+        }
+    }
+}
+
+impl State {
+    /// Checks the values which the wire format cannot rule out, such as a
+    /// zero random edge seed or a domain filter which does not fit the
+    /// display's width. Rules are checked as they are deserialised.
+    pub fn validate(&self) -> Result<(), InvalidState> {
+        // `Wrap::random` replaces a zero seed, so one only comes from a bad sender
+        if self.wrap == Wrap::Random(0) {
+            return Err(InvalidState::Wrap);
+        }
+        if self.filter.is_some_and(|filter| !filter.is_valid(WIDTH)) {
+            return Err(InvalidState::Filter);
         }
+        Ok(())
     }
 }
\ No newline at end of file
@@ -164,6 +164,11 @@ impl<
 
         let step = Step::new(self.step.is_high());
 
-        State { rule, wrap, step }
+        State {
+            rule,
+            wrap,
+            step,
+            ..State::default()
+        }
     }
 }
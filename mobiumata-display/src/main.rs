@@ -1,8 +1,6 @@
 #![no_std]
 #![no_main]
 
-use core::array;
-
 use cyw43_pio::PioSpi;
 use defmt::*;
 use embassy_executor::Spawner;
@@ -18,12 +16,15 @@ use embassy_sync::signal::Signal;
 use embassy_time::{Duration, Ticker, Timer};
 use embedded_graphics::pixelcolor::Rgb888;
 use embedded_graphics::prelude::*;
-use mobiumata_common::automaton::ElementaryCellularAutomaton;
+use mobiumata_common::automaton::{
+    seed, Ant, ElementaryCellularAutomaton, Heading, LifeLikeAutomaton, RangeCellularAutomaton,
+    SpaceTime, SplitMix64, TurmiteAutomaton,
+};
 use mobiumata_common::display::ws2812::Ws2812;
 use mobiumata_common::display::{Display, HEIGHT, WIDTH};
 use mobiumata_common::network::{init_network, udp_listen, Mode};
-use mobiumata_common::state::{State, Step};
-use rand::Rng;
+use mobiumata_common::state::{Program, State, Step};
+use rand::RngCore;
 use smart_leds::hsv::{hsv2rgb, Hsv};
 use static_cell::StaticCell;
 
@@ -38,6 +39,16 @@ bind_interrupts!(struct Irqs1 {
 });
 
 const BRIGHTNESS: u8 = 16;
+const HUE_ZERO: u8 = 15;
+const HUE_ONE: u8 = 170;
+const HUE_PARTICLE: u8 = 85;
+/// Elementary strips which have repeated with at most this period for a
+/// whole screen are reseeded.
+const STAGNANT_PERIOD: usize = 4;
+const ANTS: [Ant; 2] = [
+    Ant::new(HEIGHT / 4, WIDTH / 2, Heading::East),
+    Ant::new(3 * HEIGHT / 4, WIDTH / 2, Heading::West),
+];
 
 fn hsv(hue: u8, sat: u8, val: u8) -> Rgb888 {
     let rgb = hsv2rgb(Hsv {
@@ -48,6 +59,13 @@ fn hsv(hue: u8, sat: u8, val: u8) -> Rgb888 {
     Rgb888::new(rgb.r, rgb.g, rgb.b)
 }
 
+/// Spreads the states of a `colours` state automaton evenly between the
+/// hues used for dead and live binary cells.
+fn cell_hue(cell: u8, colours: u8) -> u8 {
+    let range = (HUE_ONE - HUE_ZERO) as u16;
+    HUE_ZERO + (range * cell as u16 / (colours.max(2) as u16 - 1)) as u8
+}
+
 #[embassy_executor::main]
 async fn main(spawner: Spawner) {
     info!("Start");
@@ -85,31 +103,117 @@ async fn main(spawner: Spawner) {
 
     spawner.spawn(udp_listen(stack, signal)).unwrap();
 
+    let seed = RoscRng.next_u64();
+    info!("Seed: {}", seed);
+    let mut rng = SplitMix64::new(seed);
+    // Rows stay as bools rather than `BitRow`s: at 8 cells a row is a single
+    // word either way, and the Life, turmite, damage and domain code all
+    // index cells directly.
     static UNIVERSE: StaticCell<[[bool; WIDTH]; HEIGHT]> = StaticCell::new();
-    let universe = UNIVERSE.init(array::from_fn(|_| {
-        array::from_fn(|_| RoscRng.gen_bool(0.5))
-    }));
+    let mut universe = UNIVERSE.init([[false; WIDTH]; HEIGHT]);
+    for row in universe.iter_mut() {
+        seed::random(row, 0.5, &mut rng);
+    }
+    static SHADOW_UNIVERSE: StaticCell<[[bool; WIDTH]; HEIGHT]> = StaticCell::new();
+    let shadow_universe = SHADOW_UNIVERSE.init(*universe);
+    static NEXT_UNIVERSE: StaticCell<[[bool; WIDTH]; HEIGHT]> = StaticCell::new();
+    let mut next_universe = NEXT_UNIVERSE.init([[false; WIDTH]; HEIGHT]);
+    static PARTICLES: StaticCell<[[bool; WIDTH]; HEIGHT]> = StaticCell::new();
+    let particles = PARTICLES.init([[false; WIDTH]; HEIGHT]);
+    static TURMITE_UNIVERSE: StaticCell<[[u8; WIDTH]; HEIGHT]> = StaticCell::new();
+    let turmite_universe = TURMITE_UNIVERSE.init([[0; WIDTH]; HEIGHT]);
+    let mut ants = ANTS;
+    let mut stagnant_generations = 0;
     let mut state = State::default();
     let mut ticker = RunStepTicker::new(state.step);
 
     loop {
         for y_update in 0..HEIGHT {
             if let Some(new_state) = signal.try_take() {
+                if new_state.program != state.program {
+                    *turmite_universe = [[0; WIDTH]; HEIGHT];
+                    ants = ANTS;
+                }
+                if let Some(new_seed) = new_state.seed.filter(|&seed| Some(seed) != state.seed) {
+                    info!("Seed: {}", new_seed);
+                    rng = SplitMix64::new(new_seed);
+                    for row in universe.iter_mut() {
+                        seed::random(row, 0.5, &mut rng);
+                    }
+                    *shadow_universe = *universe;
+                }
+                if new_state.damage {
+                    *shadow_universe = *universe;
+                    let latest = &mut shadow_universe[(y_update + HEIGHT - 1) % HEIGHT];
+                    latest[WIDTH / 2] = !latest[WIDTH / 2];
+                }
+                stagnant_generations = 0;
                 state = new_state;
                 info!("New state: {:?}", state);
             }
 
-            let automaton = ElementaryCellularAutomaton::new(state.wrap, state.rule);
-            automaton.next_row(universe, y_update);
+            match state.program {
+                Program::Elementary => {
+                    let automaton = ElementaryCellularAutomaton::new(state.wrap, state.rule);
+                    let mut diagram = SpaceTime::with_head(&mut universe[..], y_update);
+                    diagram.step(&automaton);
+                    if state.damage {
+                        SpaceTime::with_head(&mut shadow_universe[..], y_update).step(&automaton);
+                    }
+
+                    let latest = diagram.latest();
+                    if (1..=STAGNANT_PERIOD).any(|age| diagram.generation(age) == latest) {
+                        stagnant_generations += 1;
+                    } else {
+                        stagnant_generations = 0;
+                    }
+                    if stagnant_generations >= HEIGHT {
+                        info!("Reseeding stagnant strip");
+                        seed::random(&mut universe[y_update], 0.5, &mut rng);
+                        shadow_universe[y_update] = universe[y_update];
+                        stagnant_generations = 0;
+                    }
+                    state.wrap = state.wrap.next_generation();
+                }
+                Program::Life(rule) => {
+                    LifeLikeAutomaton::new(rule).next(universe, next_universe);
+                    core::mem::swap(&mut universe, &mut next_universe);
+                }
+                Program::Turmite(rule) => {
+                    TurmiteAutomaton::new(rule).step(turmite_universe, &mut ants);
+                }
+                Program::Range(rule) => {
+                    let automaton = RangeCellularAutomaton::new(state.wrap, rule);
+                    SpaceTime::with_head(&mut universe[..], y_update).step(&automaton);
+                    state.wrap = state.wrap.next_generation();
+                }
+            }
 
-            let pixels = universe.iter().enumerate().flat_map(|(y, row)| {
-                row.iter().enumerate().map(move |(x, cell)| {
-                    let mut hue = 15;
-                    let saturation = 255;
+            let program = state.program;
+            let damage = state.damage && program == Program::Elementary;
+            let filter = state.filter.filter(|_| program == Program::Elementary);
+            if let Some(filter) = filter {
+                filter.classify(universe, (y_update + 1) % HEIGHT, particles);
+            }
+            let (universe, shadow_universe, turmite_universe, particles) = (
+                &*universe,
+                &*shadow_universe,
+                &*turmite_universe,
+                &*particles,
+            );
+            let pixels = (0..HEIGHT).flat_map(move |y| {
+                (0..WIDTH).map(move |x| {
+                    let hue = match program {
+                        Program::Turmite(rule) => cell_hue(turmite_universe[y][x], rule.colours()),
+                        _ if filter.is_some() && particles[y][x] => HUE_PARTICLE,
+                        _ => cell_hue(universe[y][x] as u8, 2),
+                    };
+                    let saturation = if damage && universe[y][x] != shadow_universe[y][x] {
+                        0
+                    } else {
+                        255
+                    };
                     let value = 255;
-                    if *cell {
-                        hue = 170;
-                    }
                     Pixel(
                         Point::new(y as i32, (WIDTH - 1 - x) as i32),
                         hsv(hue, saturation, value),
@@ -2,10 +2,18 @@
 
 use core::array;
 
-use mobiumata_automaton::{ElementaryCellularAutomaton, Rule, Wrap};
+use mobiumata_automaton::{
+    damage, seed, statistics, CellularAutomaton, ElementaryCellularAutomaton,
+    MemoryCellularAutomaton, MemoryFunction, Rule, Sensitivity, SplitMix64, Wrap,
+    PERIOD_SEARCH_FACTOR,
+};
 
 #[panic_handler]
 fn panic(_info: &core::panic::PanicInfo) -> ! {
+    // Trap so the JavaScript caller sees an error rather than a hung module
+    #[cfg(target_arch = "wasm32")]
+    core::arch::wasm32::unreachable();
+    #[cfg(not(target_arch = "wasm32"))]
     loop {}
 }
 
@@ -16,31 +24,167 @@ fn ecm(rule: u8, wrap: Wrap, state: u8) -> (ElementaryCellularAutomaton, [bool;
     (automaton, state)
 }
 
-fn ecm_next(rule: u8, state: u8, wrap: Wrap) -> u8 {
+fn pack(state: [bool; 8]) -> u8 {
+    state.iter().enumerate().fold(0, |acc, (i, &cell)| acc | (cell as u8) << i)
+}
+
+/// Jumps ahead with `advance`, which skips whole cycles once `generations`
+/// exceeds the 256 possible states, so large counts from JavaScript stay fast.
+fn ecm_generation(rule: u8, state: u8, wrap: Wrap, generations: u32) -> u8 {
     let (automaton, state) = ecm(rule, wrap, state);
-    let mut next_state = [false; 8];
-    automaton.next(&state, &mut next_state);
-    next_state.iter().enumerate().fold(0, |acc, (i, &cell)| acc | (cell as u8) << i)
+    pack(automaton.advance(&state, generations as usize))
+}
+
+fn ecm_next(rule: u8, state: u8, wrap: Wrap) -> u8 {
+    ecm_generation(rule, state, wrap, 1)
 }
 
 #[no_mangle]
-pub extern fn ecm_next_zero(rule: u8, state: u8) -> u8 {
+pub extern "C" fn ecm_next_zero(rule: u8, state: u8) -> u8 {
     ecm_next(rule, state, Wrap::Zero)
 }
 
 #[no_mangle]
-pub extern fn ecm_next_one(rule: u8, state: u8) -> u8 {
+pub extern "C" fn ecm_next_one(rule: u8, state: u8) -> u8 {
     ecm_next(rule, state, Wrap::One)
 }
 
 #[no_mangle]
-pub extern fn ecm_next_wrap(rule: u8, state: u8) -> u8 {
+pub extern "C" fn ecm_next_wrap(rule: u8, state: u8) -> u8 {
     ecm_next(rule, state, Wrap::Wrap)
 }
 
+#[no_mangle]
+pub extern "C" fn ecm_next_reflect(rule: u8, state: u8) -> u8 {
+    ecm_next(rule, state, Wrap::Reflect)
+}
+
+#[no_mangle]
+pub extern "C" fn ecm_next_random(rule: u8, state: u8, seed: u32) -> u8 {
+    ecm_next(rule, state, Wrap::random(seed))
+}
+
+#[no_mangle]
+pub extern "C" fn ecm_next_input(rule: u8, state: u8, left: bool, right: bool) -> u8 {
+    ecm_next(rule, state, Wrap::input(left, right))
+}
+
+/// Returns the state `generations` generations after `state`.
+#[no_mangle]
+pub extern "C" fn ecm_wrap_generation(rule: u8, state: u8, generations: u32) -> u8 {
+    ecm_generation(rule, state, Wrap::Wrap, generations)
+}
+
+#[no_mangle]
+pub extern "C" fn ecm_zero_generation(rule: u8, state: u8, generations: u32) -> u8 {
+    ecm_generation(rule, state, Wrap::Zero, generations)
+}
+
+/// Steps an automaton with memory, where `history` packs up to four
+/// generations one per byte, most recent in the lowest byte.
+#[no_mangle]
+pub extern "C" fn ecm_memory_next_wrap(rule: u8, history: u32, depth: u8, parity: bool) -> u8 {
+    let function = if parity {
+        MemoryFunction::Parity
+    } else {
+        MemoryFunction::Majority
+    };
+    let automaton = MemoryCellularAutomaton::new(
+        Wrap::Wrap,
+        Rule::new(rule),
+        depth.clamp(1, 4).into(),
+        function,
+    );
+    let history: [[bool; 8]; 4] =
+        array::from_fn(|age| array::from_fn(|i| (history >> (8 * age + i)) & 1 == 1));
+    let mut next_state = [false; 8];
+    automaton.next(&history, &mut next_state);
+    pack(next_state)
+}
+
+/// Steps the seed passed to `ecm_next_random` on to the next generation.
+#[no_mangle]
+pub extern "C" fn random_wrap_next_seed(seed: u32) -> u32 {
+    Wrap::next_seed(seed)
+}
+
+#[no_mangle]
+pub extern "C" fn seed_centre() -> u8 {
+    let mut state = [false; 8];
+    seed::centre(&mut state);
+    pack(state)
+}
+
+/// Fills a row the same way the display does for the same seed and density.
+#[no_mangle]
+pub extern "C" fn seed_random(seed: u64, density: f32) -> u8 {
+    let mut state = [false; 8];
+    seed::random(&mut state, density, &mut SplitMix64::new(seed));
+    pack(state)
+}
+
+/// Repeats the lowest `length` bits of `motif` across the row.
+#[no_mangle]
+pub extern "C" fn seed_motif(motif: u8, length: u8) -> u8 {
+    let motif: [bool; 8] = array::from_fn(|i| (motif >> i) & 1 == 1);
+    let mut state = [false; 8];
+    seed::motif(&mut state, &motif[..length.clamp(1, 8) as usize]);
+    pack(state)
+}
+
+/// Flips the centre cell of a random 63 cell row and measures how the damage
+/// spreads over 64 generations. Each export returns NaN if it cannot be
+/// measured.
+fn ecm_sensitivity(rule: u8, seed: u64) -> Option<Sensitivity> {
+    let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(rule));
+    let mut initial = [false; 63];
+    seed::random(&mut initial, 0.5, &mut SplitMix64::new(seed));
+    damage::measure(&automaton, initial, 31, 64).ok()
+}
+
+#[no_mangle]
+pub extern "C" fn ecm_lyapunov(rule: u8, seed: u64) -> f32 {
+    ecm_sensitivity(rule, seed).map_or(f32::NAN, |sensitivity| sensitivity.lyapunov)
+}
+
+#[no_mangle]
+pub extern "C" fn ecm_left_speed(rule: u8, seed: u64) -> f32 {
+    ecm_sensitivity(rule, seed).map_or(f32::NAN, |sensitivity| sensitivity.left_speed)
+}
+
+#[no_mangle]
+pub extern "C" fn ecm_right_speed(rule: u8, seed: u64) -> f32 {
+    ecm_sensitivity(rule, seed).map_or(f32::NAN, |sensitivity| sensitivity.right_speed)
+}
+
+/// Entropy in bits per cell of blocks of `size` cells, measured over 64
+/// generations of a random 63 cell row after it has settled for 64
+/// generations, for sorting rules by complexity.
+#[no_mangle]
+pub extern "C" fn ecm_block_entropy(rule: u8, seed: u64, size: u8) -> f32 {
+    let automaton = ElementaryCellularAutomaton::new(Wrap::Wrap, Rule::new(rule));
+    let mut initial = [false; 63];
+    seed::random(&mut initial, 0.5, &mut SplitMix64::new(seed));
+    let size = size.clamp(1, statistics::MAX_BLOCK_SIZE as u8) as usize;
+    let window = automaton.evolve(initial).skip(64).take(64);
+    statistics::block_entropy(window, size) / size as f32
+}
+
+/// Generations searched for a cycle, the height of the display.
+const PERIOD_MAX: usize = 192;
+
+/// Returned by the period and transient exports when no cycle is found.
+const NO_CYCLE: u8 = u8::MAX;
+
+/// Period of the cycle reached and repeated within `PERIOD_MAX` generations,
+/// or `NO_CYCLE`.
 fn ecm_period(rule: u8, state: u8, wrap: Wrap) -> u8 {
     let (automaton, state) = ecm(rule, wrap, state);
-    automaton.period::<8, 192>(&state).unwrap_or(0) as u8
+    let period = automaton.try_period::<8, PERIOD_MAX>(&state);
+    period
+        .ok()
+        .flatten()
+        .map_or(NO_CYCLE, |period| period as u8)
 }
 
 #[no_mangle]
@@ -57,4 +201,40 @@ pub extern "C" fn ecm_wrap_period(rule: u8, state: u8) -> u8 {
 #[no_mangle]
 pub extern "C" fn ecm_zero_period(rule: u8, state: u8) -> u8 {
     ecm_period(rule, state, Wrap::Zero)
-}
\ No newline at end of file
+}
+
+#[no_mangle]
+pub extern "C" fn ecm_reflect_period(rule: u8, state: u8) -> u8 {
+    ecm_period(rule, state, Wrap::Reflect)
+}
+
+/// Generations before the row reaches its cycle, searching as long as
+/// `ecm_period`, or `NO_CYCLE`.
+fn ecm_transient(rule: u8, state: u8, wrap: Wrap) -> u8 {
+    let (automaton, state) = ecm(rule, wrap, state);
+    let cycle = automaton.try_cycle::<8>(&state, PERIOD_SEARCH_FACTOR * PERIOD_MAX);
+    cycle
+        .ok()
+        .flatten()
+        .map_or(NO_CYCLE, |cycle| cycle.transient as u8)
+}
+
+#[no_mangle]
+pub extern "C" fn ecm_zero_transient(rule: u8, state: u8) -> u8 {
+    ecm_transient(rule, state, Wrap::Zero)
+}
+
+#[no_mangle]
+pub extern "C" fn ecm_one_transient(rule: u8, state: u8) -> u8 {
+    ecm_transient(rule, state, Wrap::One)
+}
+
+#[no_mangle]
+pub extern "C" fn ecm_wrap_transient(rule: u8, state: u8) -> u8 {
+    ecm_transient(rule, state, Wrap::Wrap)
+}
+
+#[no_mangle]
+pub extern "C" fn ecm_reflect_transient(rule: u8, state: u8) -> u8 {
+    ecm_transient(rule, state, Wrap::Reflect)
+}